@@ -73,7 +73,7 @@ fn main() -> Result<(), JanusError> {
     }
     
     // Create a commit
-    let commit = git::create_commit(repo_path.clone(), "Implement user profile feature".to_string())?;
+    let commit = git::create_commit(repo_path.clone(), "Implement user profile feature".to_string(), None)?;
     println!("Created commit: {} - {}", commit.short_id, commit.summary);
     
     // 3. Bugfix on main branch
@@ -91,7 +91,7 @@ fn main() -> Result<(), JanusError> {
     git::stage_file(repo_path.clone(), "bug_fix.txt".to_string())?;
     println!("Staged bugfix file");
     
-    let bugfix_commit = git::create_commit(repo_path.clone(), "Fix critical bug".to_string())?;
+    let bugfix_commit = git::create_commit(repo_path.clone(), "Fix critical bug".to_string(), None)?;
     println!("Created bugfix commit: {} - {}", bugfix_commit.short_id, bugfix_commit.summary);
     
     // 4. Continue feature development
@@ -112,14 +112,14 @@ fn main() -> Result<(), JanusError> {
     
     // Stage and commit
     git::stage_file(repo_path.clone(), "user_profile.txt".to_string())?;
-    let update_commit = git::create_commit(repo_path.clone(), "Update user profile settings".to_string())?;
+    let update_commit = git::create_commit(repo_path.clone(), "Update user profile settings".to_string(), None)?;
     println!("Created update commit: {} - {}", update_commit.short_id, update_commit.summary);
     
     // 5. Merge bugfix into feature
     println!("\n{}{}Step 6: Merging bugfix into feature branch{}", BOLD, GREEN, RESET);
     
     // Merge main into feature branch
-    let merge_result = git::merge_branch(repo_path.clone(), "main".to_string())?;
+    let merge_result = git::merge_branch(repo_path.clone(), "main".to_string(), None)?;
     if merge_result.success {
         println!("Successfully merged main into {}: {}", feature_branch, merge_result.message);
     } else if merge_result.has_conflicts {
@@ -136,7 +136,7 @@ fn main() -> Result<(), JanusError> {
     // Add final feature file
     fs::write(test_dir.join("feature_complete.txt"), "Feature is now complete")?;
     git::stage_file(repo_path.clone(), "feature_complete.txt".to_string())?;
-    let final_commit = git::create_commit(repo_path.clone(), "Complete user profile feature".to_string())?;
+    let final_commit = git::create_commit(repo_path.clone(), "Complete user profile feature".to_string(), None)?;
     println!("Created final feature commit: {} - {}", final_commit.short_id, final_commit.summary);
     
     // Switch to main
@@ -144,7 +144,7 @@ fn main() -> Result<(), JanusError> {
     println!("Checked out branch: main");
     
     // Merge feature into main
-    let feature_merge = git::merge_branch(repo_path.clone(), feature_branch.to_string())?;
+    let feature_merge = git::merge_branch(repo_path.clone(), feature_branch.to_string(), None)?;
     if feature_merge.success {
         println!("Successfully merged {} into main: {}", feature_branch, feature_merge.message);
     } else if feature_merge.has_conflicts {
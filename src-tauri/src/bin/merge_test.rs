@@ -102,7 +102,7 @@ fn run_merge_tests(repo_path: &PathBuf) -> Result<(), JanusError> {
     
     // Merge the feature branch (should not have conflicts)
     println!("Merging feature-branch into main...");
-    match git::merge_branch(repo_path_str.clone(), "feature-branch".to_string()) {
+    match git::merge_branch(repo_path_str.clone(), "feature-branch".to_string(), None) {
         Ok(result) => {
             println!("Merge result: {}", result.message);
             println!("Success: {}", result.success);
@@ -135,7 +135,7 @@ fn run_merge_tests(repo_path: &PathBuf) -> Result<(), JanusError> {
     
     // Merge the conflict branch (should have conflicts)
     println!("Merging conflict-branch into main...");
-    match git::merge_branch(repo_path_str.clone(), "conflict-branch".to_string()) {
+    match git::merge_branch(repo_path_str.clone(), "conflict-branch".to_string(), None) {
         Ok(result) => {
             println!("Merge result: {}", result.message);
             println!("Success: {}", result.success);
@@ -0,0 +1,246 @@
+use crate::error::JanusError;
+use crate::git::open_repo;
+use crate::paths;
+use git2::{Commit, Oid, Repository};
+use lazy_static::lazy_static;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+// Maximum number of blames to keep in memory
+const MAX_CACHED_BLAMES: usize = 64;
+
+/// A run of consecutive lines last changed by the same commit
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BlameHunk {
+    pub commit_id: String,
+    pub summary: String,
+    pub author: String,
+    pub author_email: String,
+    pub time: i64,
+    /// Path of the file in the blamed commit, which differs after a rename
+    pub orig_path: String,
+    /// One-based line number in the blamed commit's version of the file
+    pub orig_start_line: usize,
+    /// One-based line number in the requested revision
+    pub start_line: usize,
+    pub lines: usize,
+    /// True when the line predates the oldest commit considered
+    pub boundary: bool,
+}
+
+/// Options for blaming a file
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct BlameOptions {
+    /// One-based, inclusive line range to blame
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+    /// Ignore whitespace-only changes, like `git blame -w`
+    pub ignore_whitespace: bool,
+    /// Skip `blame.ignoreRevsFile`, like `git blame --no-ignore-revs-file`
+    pub no_ignore_revs: bool,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct BlameCacheKey {
+    repo: String,
+    path: String,
+    head: Option<Oid>,
+    commit: Oid,
+    options: BlameOptions,
+    ignored: Vec<Oid>,
+}
+
+lazy_static! {
+    static ref BLAME_CACHE: Mutex<HashMap<BlameCacheKey, Vec<BlameHunk>>> = Mutex::new(HashMap::new());
+}
+
+/// Reads the commits listed in `blame.ignoreRevsFile`, skipping comments and unknown revisions
+fn ignored_revisions(repo: &Repository) -> Result<Vec<Oid>, JanusError> {
+    let config = repo.config()?;
+    let Ok(file) = config.get_path("blame.ignoreRevsFile") else { return Ok(Vec::new()) };
+    let file = match (file.is_relative(), repo.workdir()) {
+        (true, Some(workdir)) => workdir.join(file),
+        _ => file,
+    };
+
+    let content = fs::read_to_string(&file).map_err(|e| {
+        error!("Failed to read {}: {}", file.display(), e);
+        JanusError::IoError(format!("Failed to read blame.ignoreRevsFile {}: {}", file.display(), e))
+    })?;
+
+    let mut revisions = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        match repo.revparse_single(line).and_then(|obj| obj.peel_to_commit()) {
+            Ok(commit) => revisions.push(commit.id()),
+            Err(e) => warn!("Ignoring unknown revision {} in blame.ignoreRevsFile: {}", line, e),
+        }
+    }
+    revisions.sort();
+    revisions.dedup();
+    Ok(revisions)
+}
+
+/// Blames `lines` lines of `path` as of `commit`, starting at one-based `start`
+fn blame_range(
+    repo: &Repository,
+    path: &Path,
+    commit: &Commit,
+    start: usize,
+    lines: usize,
+    options: &BlameOptions,
+) -> Result<Vec<BlameHunk>, JanusError> {
+    let mut blame_options = git2::BlameOptions::new();
+    blame_options
+        .newest_commit(commit.id())
+        .ignore_whitespace(options.ignore_whitespace)
+        .min_line(start)
+        .max_line(start + lines - 1);
+
+    let blame = repo.blame_file(path, Some(&mut blame_options)).map_err(|e| {
+        error!("Failed to blame {}: {}", path.display(), e);
+        JanusError::GitError(format!("Failed to blame {}: {}", path.display(), e))
+    })?;
+
+    let mut hunks = Vec::new();
+    for hunk in blame.iter() {
+        let signature = hunk.final_signature();
+        let summary = repo
+            .find_commit(hunk.final_commit_id())
+            .ok()
+            .and_then(|c| c.summary().map(|s| s.to_string()))
+            .unwrap_or_default();
+        hunks.push(BlameHunk {
+            commit_id: hunk.final_commit_id().to_string(),
+            summary,
+            author: signature.name().unwrap_or("").to_string(),
+            author_email: signature.email().unwrap_or("").to_string(),
+            time: signature.when().seconds(),
+            orig_path: hunk.path().unwrap_or(path).to_string_lossy().to_string(),
+            orig_start_line: hunk.orig_start_line(),
+            start_line: hunk.final_start_line(),
+            lines: hunk.lines_in_hunk(),
+            boundary: hunk.is_boundary(),
+        });
+    }
+    Ok(hunks)
+}
+
+fn line_count(repo: &Repository, commit: &Commit, path: &Path) -> Option<usize> {
+    let entry = commit.tree().ok()?.get_path(path).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(blob.content().split_inclusive(|b| *b == b'\n').count())
+}
+
+/// Passes the blame for hunks owned by an ignored commit on to that commit's first parent.
+/// Like git, this assumes the ignored commit kept the lines in place, which holds for
+/// reformatting commits; lines the parent does not have keep their original attribution.
+fn pass_over_ignored(
+    repo: &Repository,
+    hunks: Vec<BlameHunk>,
+    ignored: &HashSet<Oid>,
+    options: &BlameOptions,
+) -> Result<Vec<BlameHunk>, JanusError> {
+    let mut result = Vec::new();
+    for hunk in hunks {
+        let owner = Oid::from_str(&hunk.commit_id)?;
+        let parent = match repo.find_commit(owner).and_then(|c| c.parent(0)) {
+            Ok(parent) if ignored.contains(&owner) => parent,
+            _ => {
+                result.push(hunk);
+                continue;
+            }
+        };
+
+        let path = Path::new(&hunk.orig_path);
+        let available = line_count(repo, &parent, path)
+            .unwrap_or(0)
+            .saturating_sub(hunk.orig_start_line - 1)
+            .min(hunk.lines);
+        if available == 0 {
+            result.push(hunk);
+            continue;
+        }
+
+        let offset = hunk.start_line as isize - hunk.orig_start_line as isize;
+        let earlier = blame_range(repo, path, &parent, hunk.orig_start_line, available, options)?;
+        for mut earlier_hunk in pass_over_ignored(repo, earlier, ignored, options)? {
+            earlier_hunk.start_line = (earlier_hunk.start_line as isize + offset) as usize;
+            result.push(earlier_hunk);
+        }
+        if available < hunk.lines {
+            result.push(BlameHunk {
+                orig_start_line: hunk.orig_start_line + available,
+                start_line: hunk.start_line + available,
+                lines: hunk.lines - available,
+                ..hunk
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Blames a file at a revision, or at HEAD when no revision is given
+#[tauri::command]
+pub fn get_blame(
+    repo_path: String,
+    file_path: String,
+    revspec: Option<String>,
+    options: Option<BlameOptions>,
+) -> Result<Vec<BlameHunk>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let options = options.unwrap_or_default();
+    let revspec = revspec.unwrap_or_else(|| "HEAD".to_string());
+
+    let commit = repo.revparse_single(&revspec).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to find {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to find {}: {}", revspec, e))
+    })?;
+
+    let path = Path::new(&file_path);
+    let total_lines = line_count(&repo, &commit, path)
+        .ok_or_else(|| JanusError::ValidationError(format!("{} does not exist at {}", file_path, revspec)))?;
+
+    let start = options.start_line.unwrap_or(1).max(1);
+    let end = options.end_line.unwrap_or(total_lines).min(total_lines);
+    if total_lines == 0 || start > end {
+        return Ok(Vec::new());
+    }
+
+    let ignored = if options.no_ignore_revs { Vec::new() } else { ignored_revisions(&repo)? };
+    let key = BlameCacheKey {
+        repo: repo.path().to_string_lossy().to_string(),
+        path: file_path.clone(),
+        head: repo.head().ok().and_then(|head| head.target()),
+        commit: commit.id(),
+        options: options.clone(),
+        ignored: ignored.clone(),
+    };
+
+    {
+        let cache = BLAME_CACHE.lock().map_err(|e| JanusError::UnknownError(format!("Failed to lock blame cache: {}", e)))?;
+        if let Some(hunks) = cache.get(&key) {
+            return Ok(hunks.clone());
+        }
+    }
+
+    let hunks = blame_range(&repo, path, &commit, start, end - start + 1, &options)?;
+    let hunks = pass_over_ignored(&repo, hunks, &ignored.into_iter().collect(), &options)?;
+
+    let mut cache = BLAME_CACHE.lock().map_err(|e| JanusError::UnknownError(format!("Failed to lock blame cache: {}", e)))?;
+    if cache.len() >= MAX_CACHED_BLAMES {
+        cache.clear();
+    }
+    cache.insert(key, hunks.clone());
+
+    Ok(hunks)
+}
@@ -0,0 +1,55 @@
+use crate::error::JanusError;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    static ref ACTIVE: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+/// Cancellation flag for a long-running command, registered under an id chosen by the frontend.
+/// The registration is dropped with the token.
+pub struct CancelToken {
+    id: String,
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Registers an operation id, failing if another operation is already using it
+    pub fn register(id: &str) -> Result<Self, JanusError> {
+        let mut active = ACTIVE.lock().map_err(|e| JanusError::UnknownError(format!("Failed to lock operations: {}", e)))?;
+        if active.contains_key(id) {
+            return Err(JanusError::ValidationError(format!("Operation {} is already running", id)));
+        }
+
+        let flag = Arc::new(AtomicBool::new(false));
+        active.insert(id.to_string(), flag.clone());
+        Ok(Self { id: id.to_string(), flag })
+    }
+
+    pub fn flag(&self) -> &AtomicBool {
+        &self.flag
+    }
+}
+
+impl Drop for CancelToken {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.remove(&self.id);
+        }
+    }
+}
+
+/// Asks a running operation to stop. Returns false if no operation has that id.
+#[tauri::command]
+pub fn cancel_operation(operation_id: String) -> Result<bool, JanusError> {
+    let active = ACTIVE.lock().map_err(|e| JanusError::UnknownError(format!("Failed to lock operations: {}", e)))?;
+    match active.get(&operation_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -0,0 +1,214 @@
+use crate::cancellation::CancelToken;
+use crate::error::JanusError;
+use crate::git::{remember_repository, RepoInfo};
+use crate::paths;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{ErrorClass, ErrorCode, FetchOptions, RemoteCallbacks, Repository, SubmoduleUpdateOptions};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted as a clone makes progress
+pub const CLONE_PROGRESS_EVENT: &str = "clone-progress";
+
+/// Options for `clone_repository`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CloneOptions {
+    /// Branch to check out instead of the remote's HEAD
+    pub branch: Option<String>,
+    /// Fetch only this many commits of history. Ignored for local sources, which
+    /// libgit2 cannot clone shallowly.
+    pub depth: Option<u32>,
+    pub bare: bool,
+    /// Also clone and check out submodules, recursively
+    pub recurse_submodules: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneStage {
+    Receiving,
+    Resolving,
+    Checkout,
+    Submodules,
+}
+
+/// Sent with `CLONE_PROGRESS_EVENT` whenever a stage advances by at least a percent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CloneProgress {
+    pub operation_id: String,
+    pub stage: CloneStage,
+    pub current: usize,
+    pub total: usize,
+    pub received_bytes: usize,
+}
+
+fn is_local(url: &str) -> bool {
+    url.starts_with("file://") || Path::new(url).is_absolute()
+}
+
+fn clone_error(url: &str, e: git2::Error) -> JanusError {
+    error!("Failed to clone {}: {}", url, e);
+    let message = format!("Failed to clone {}: {}", url, e);
+    match (e.code(), e.class()) {
+        (ErrorCode::Auth, _) | (_, ErrorClass::Ssh) => JanusError::AuthError(message),
+        (_, ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssl) => JanusError::NetworkError(message),
+        _ => JanusError::GitError(message),
+    }
+}
+
+fn fetch_options<'a>(cancel: &'a AtomicBool, report: &'a dyn Fn(CloneStage, usize, usize, usize)) -> FetchOptions<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    // Returning false from the callback makes libgit2 stop the transfer
+    callbacks.transfer_progress(move |stats| {
+        if stats.received_objects() < stats.total_objects() {
+            report(CloneStage::Receiving, stats.received_objects(), stats.total_objects(), stats.received_bytes());
+        } else {
+            report(CloneStage::Resolving, stats.indexed_deltas(), stats.total_deltas(), stats.received_bytes());
+        }
+        !cancel.load(Ordering::Relaxed)
+    });
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    options
+}
+
+/// Brings submodules up to date, then their submodules
+fn update_submodules(
+    repo: &Repository,
+    cancel: &AtomicBool,
+    report: &dyn Fn(CloneStage, usize, usize, usize),
+    top_level: bool,
+) -> Result<(), JanusError> {
+    let mut submodules = repo.submodules()?;
+    let total = submodules.len();
+    for (done, submodule) in submodules.iter_mut().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(JanusError::GitError("Clone was cancelled".to_string()));
+        }
+        if top_level {
+            report(CloneStage::Submodules, done, total, 0);
+        }
+
+        // Progress is reported per submodule rather than per object
+        let silent = |_: CloneStage, _: usize, _: usize, _: usize| {};
+        let mut options = SubmoduleUpdateOptions::new();
+        options.fetch(fetch_options(cancel, &silent));
+        submodule.update(true, Some(&mut options)).map_err(|e| {
+            error!("Failed to update submodule {}: {}", submodule.path().display(), e);
+            JanusError::GitError(format!("Failed to update submodule {}: {}", submodule.path().display(), e))
+        })?;
+        if let Ok(nested) = submodule.open() {
+            update_submodules(&nested, cancel, report, false)?;
+        }
+    }
+    if top_level && total > 0 {
+        report(CloneStage::Submodules, total, total, 0);
+    }
+    Ok(())
+}
+
+fn clone_into(
+    url: &str,
+    dest: &Path,
+    options: &CloneOptions,
+    cancel: &AtomicBool,
+    report: &dyn Fn(CloneStage, usize, usize, usize),
+) -> Result<Repository, JanusError> {
+    let mut fetch = fetch_options(cancel, report);
+    match options.depth {
+        Some(depth) if is_local(url) => warn!("Ignoring depth {} for local clone of {}", depth, url),
+        Some(depth) => {
+            fetch.depth(depth as i32);
+        }
+        None => {}
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.progress(|_, current, total| report(CloneStage::Checkout, current, total, 0));
+
+    let mut builder = RepoBuilder::new();
+    builder.bare(options.bare).fetch_options(fetch).with_checkout(checkout);
+    if let Some(branch) = &options.branch {
+        builder.branch(branch);
+    }
+
+    let repo = builder.clone(url, dest).map_err(|e| match cancel.load(Ordering::Relaxed) {
+        true => JanusError::GitError(format!("Clone of {} was cancelled", url)),
+        false => clone_error(url, e),
+    })?;
+
+    if options.recurse_submodules && !options.bare {
+        update_submodules(&repo, cancel, report, true)?;
+    }
+    Ok(repo)
+}
+
+/// Clones `url` into `dest`, reporting progress as it goes. On failure or cancellation
+/// anything created in `dest` is removed again.
+pub fn clone(
+    url: &str,
+    dest: &Path,
+    operation_id: &str,
+    options: &CloneOptions,
+    cancel: &AtomicBool,
+    on_progress: &dyn Fn(&CloneProgress),
+) -> Result<Repository, JanusError> {
+    paths::validate_cli_arg(url)?;
+    if !dest.is_absolute() {
+        return Err(JanusError::ValidationError(format!("Clone destination {} must be absolute", dest.display())));
+    }
+    let existed = dest.exists();
+    if existed && fs::read_dir(dest)?.next().is_some() {
+        return Err(JanusError::ValidationError(format!("{} already exists and is not empty", dest.display())));
+    }
+
+    // Only report when the stage changes or moves on by a percent, as libgit2 calls back per object
+    let last = Cell::new(None);
+    let report = |stage: CloneStage, current: usize, total: usize, received_bytes: usize| {
+        let percent = (current * 100).checked_div(total).unwrap_or(100);
+        if last.get() == Some((stage, percent)) {
+            return;
+        }
+        last.set(Some((stage, percent)));
+        on_progress(&CloneProgress { operation_id: operation_id.to_string(), stage, current, total, received_bytes });
+    };
+
+    let result = clone_into(url, dest, options, cancel, &report);
+    if result.is_err() {
+        let cleanup = fs::remove_dir_all(dest).and_then(|_| if existed { fs::create_dir(dest) } else { Ok(()) });
+        if let Err(e) = cleanup {
+            warn!("Failed to clean up {} after a failed clone: {}", dest.display(), e);
+        }
+    }
+    result
+}
+
+/// Clones a repository and adds it to recent repositories, streaming `CLONE_PROGRESS_EVENT`.
+/// Cancel it with `cancel_operation(operation_id)`.
+#[tauri::command(async)]
+pub fn clone_repository(
+    app: AppHandle,
+    url: String,
+    dest: String,
+    operation_id: String,
+    options: Option<CloneOptions>,
+) -> Result<RepoInfo, JanusError> {
+    let token = CancelToken::register(&operation_id)?;
+    let dest = PathBuf::from(dest);
+
+    let repo = clone(&url, &dest, &operation_id, &options.unwrap_or_default(), token.flag(), &|progress| {
+        if let Err(e) = app.emit(CLONE_PROGRESS_EVENT, progress.clone()) {
+            warn!("Failed to emit {} event: {}", CLONE_PROGRESS_EVENT, e);
+        }
+    })?;
+
+    info!("Cloned {} into {}", url, dest.display());
+    let path = repo.workdir().unwrap_or(repo.path()).to_path_buf();
+    remember_repository(path)
+}
@@ -0,0 +1,300 @@
+use crate::error::JanusError;
+use crate::git::{self, conflict_path, index_conflicts, open_repo};
+use crate::paths;
+use git2::{FileMode, IndexEntry, Repository};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Length of the `<<<<<<<`, `|||||||`, `=======` and `>>>>>>>` markers
+const MARKER_SIZE: usize = 7;
+
+/// One side of a conflict as recorded in the index
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflictSide {
+    pub oid: String,
+    pub mode: u32,
+    pub is_binary: bool,
+    /// File content, or None for binary files
+    pub content: Option<String>,
+}
+
+/// A conflicted hunk parsed from the working file's markers
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConflictRegion {
+    /// One-based line numbers of the opening and closing markers
+    pub start_line: usize,
+    pub end_line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: String,
+    /// Only present for diff3-style markers
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+/// Everything the conflict viewer needs for one path
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConflictContent {
+    pub path: String,
+    /// A missing side means that branch deleted the file, or it did not exist in the base
+    pub base: Option<ConflictSide>,
+    pub ours: Option<ConflictSide>,
+    pub theirs: Option<ConflictSide>,
+    pub working: Option<String>,
+    pub regions: Vec<ConflictRegion>,
+}
+
+/// How to resolve a conflicted file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    /// Keep our lines followed by theirs in every conflicted region
+    Both,
+    /// Use merged content supplied by the user
+    Content { content: String },
+}
+
+fn conflict_side(repo: &Repository, entry: Option<&IndexEntry>) -> Result<Option<ConflictSide>, JanusError> {
+    let Some(entry) = entry else { return Ok(None) };
+    let blob = repo.find_blob(entry.id).map_err(|e| {
+        error!("Failed to read conflict blob {}: {}", entry.id, e);
+        JanusError::GitError(format!("Failed to read conflict blob {}: {}", entry.id, e))
+    })?;
+
+    Ok(Some(ConflictSide {
+        oid: entry.id.to_string(),
+        mode: entry.mode,
+        is_binary: blob.is_binary(),
+        content: if blob.is_binary() { None } else { Some(String::from_utf8_lossy(blob.content()).to_string()) },
+    }))
+}
+
+fn find_conflict(repo: &Repository, file_path: &str) -> Result<git2::IndexConflict, JanusError> {
+    let index = repo.index()?;
+    index_conflicts(&index)?
+        .into_iter()
+        .find(|conflict| conflict_path(conflict).as_deref() == Some(file_path))
+        .ok_or_else(|| JanusError::ValidationError(format!("{} is not conflicted", file_path)))
+}
+
+/// Returns the text after a conflict marker if the line starts with one
+fn marker_label(line: &str, marker: char) -> Option<&str> {
+    let rest = line.strip_prefix(&marker.to_string().repeat(MARKER_SIZE))?;
+    if rest.starts_with(marker) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// Parses the conflict regions out of a file with conflict markers
+pub fn parse_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    enum Section {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut regions = Vec::new();
+    let mut section = Section::Outside;
+    let mut current: Option<ConflictRegion> = None;
+
+    for (number, line) in content.split_inclusive('\n').enumerate() {
+        let text = line.trim_end_matches(['\n', '\r']);
+        match section {
+            Section::Outside => {
+                if let Some(label) = marker_label(text, '<') {
+                    current = Some(ConflictRegion {
+                        start_line: number + 1,
+                        end_line: number + 1,
+                        ours_label: label.to_string(),
+                        theirs_label: String::new(),
+                        ours: String::new(),
+                        base: None,
+                        theirs: String::new(),
+                    });
+                    section = Section::Ours;
+                }
+            }
+            Section::Ours | Section::Base => {
+                let Some(region) = current.as_mut() else { continue };
+                if marker_label(text, '|').is_some() {
+                    region.base = Some(String::new());
+                    section = Section::Base;
+                } else if text == "=".repeat(MARKER_SIZE) {
+                    section = Section::Theirs;
+                } else if let (Section::Base, Some(base)) = (&section, region.base.as_mut()) {
+                    base.push_str(line);
+                } else {
+                    region.ours.push_str(line);
+                }
+            }
+            Section::Theirs => {
+                let Some(region) = current.as_mut() else { continue };
+                if let Some(label) = marker_label(text, '>') {
+                    region.theirs_label = label.to_string();
+                    region.end_line = number + 1;
+                    regions.extend(current.take());
+                    section = Section::Outside;
+                } else {
+                    region.theirs.push_str(line);
+                }
+            }
+        }
+    }
+
+    regions
+}
+
+/// Replaces every conflict region with our lines followed by theirs
+fn take_both(content: &str) -> Result<String, JanusError> {
+    let regions = parse_conflict_regions(content);
+    if regions.is_empty() {
+        return Err(JanusError::ValidationError("The file has no conflict markers left".to_string()));
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut regions = regions.iter().peekable();
+    for (number, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = number + 1;
+        match regions.peek() {
+            Some(region) if line_number == region.start_line => {
+                result.push_str(&region.ours);
+                result.push_str(&region.theirs);
+            }
+            Some(region) if line_number > region.start_line && line_number < region.end_line => {}
+            Some(region) if line_number == region.end_line => {
+                regions.next();
+            }
+            _ => result.push_str(line),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Gets the base, ours and theirs versions of a conflicted file and its conflict regions
+#[tauri::command]
+pub fn get_conflict(repo_path: String, file_path: String) -> Result<ConflictContent, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let conflict = find_conflict(&repo, &file_path)?;
+
+    let working = repo
+        .workdir()
+        .and_then(|workdir| fs::read(workdir.join(&file_path)).ok())
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+    let regions = working.as_deref().map(parse_conflict_regions).unwrap_or_default();
+
+    Ok(ConflictContent {
+        path: file_path,
+        base: conflict_side(&repo, conflict.ancestor.as_ref())?,
+        ours: conflict_side(&repo, conflict.our.as_ref())?,
+        theirs: conflict_side(&repo, conflict.their.as_ref())?,
+        working,
+        regions,
+    })
+}
+
+/// Writes a file the way git checks out an entry with `mode`: a symlink to `content`, or a
+/// regular file that is executable or not
+fn write_with_mode(path: &Path, content: &[u8], mode: u32) -> std::io::Result<()> {
+    // Replace rather than write through whatever is there, which may itself be a symlink
+    if path.symlink_metadata().is_ok() {
+        fs::remove_file(path)?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::PermissionsExt;
+        if mode == u32::from(FileMode::Link) {
+            return std::os::unix::fs::symlink(std::ffi::OsStr::from_bytes(content), path);
+        }
+        fs::write(path, content)?;
+        if mode == u32::from(FileMode::BlobExecutable) {
+            // Executable by whoever may read it, as git does under the umask
+            let permissions = fs::metadata(path)?.permissions().mode();
+            fs::set_permissions(path, fs::Permissions::from_mode(permissions | (permissions & 0o444) >> 2))?;
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mode;
+        fs::write(path, content)
+    }
+}
+
+/// Replaces the conflict entries of a path with one side, keeping that side's mode
+fn stage_side(repo: &Repository, mut entry: IndexEntry) -> Result<(), git2::Error> {
+    let mut index = repo.index()?;
+    let path = PathBuf::from(String::from_utf8_lossy(&entry.path).to_string());
+    index.remove_path(&path)?;
+    entry.flags &= !0x3000;
+    index.add(&entry)?;
+    index.write()
+}
+
+/// Resolves a conflicted file and stages the result
+#[tauri::command]
+pub fn resolve_conflict(repo_path: String, file_path: String, resolution: ConflictResolution) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let conflict = find_conflict(&repo, &file_path)?;
+    let full_path = repo
+        .workdir()
+        .ok_or_else(|| JanusError::GitError("Cannot resolve conflicts in a bare repository".to_string()))?
+        .join(&file_path);
+
+    // Taking one side keeps its file mode too, so a symlink or executable stays one
+    if let ConflictResolution::Ours | ConflictResolution::Theirs = resolution {
+        let side = match resolution {
+            ConflictResolution::Ours => conflict.our,
+            _ => conflict.their,
+        };
+        if let Some(entry) = side {
+            let content = repo.find_blob(entry.id)?.content().to_vec();
+            write_with_mode(&full_path, &content, entry.mode).map_err(|e| {
+                error!("Failed to write resolution for {}: {}", file_path, e);
+                JanusError::IoError(format!("Failed to write resolution for {}: {}", file_path, e))
+            })?;
+            stage_side(&repo, entry).map_err(|e| {
+                error!("Failed to mark {} as resolved: {}", file_path, e);
+                JanusError::GitError(format!("Failed to mark {} as resolved: {}", file_path, e))
+            })?;
+            info!("Resolved conflict in {}", file_path);
+            return Ok(());
+        }
+    }
+
+    // None means the resolution deletes the file
+    let content: Option<Vec<u8>> = match &resolution {
+        ConflictResolution::Ours | ConflictResolution::Theirs => None,
+        ConflictResolution::Both => {
+            let working = fs::read_to_string(&full_path).map_err(|e| {
+                JanusError::IoError(format!("Failed to read {}: {}", file_path, e))
+            })?;
+            Some(take_both(&working)?.into_bytes())
+        }
+        ConflictResolution::Content { content } => Some(content.clone().into_bytes()),
+    };
+
+    match content {
+        Some(content) => fs::write(&full_path, content),
+        None if full_path.symlink_metadata().is_ok() => fs::remove_file(&full_path),
+        None => Ok(()),
+    }
+    .map_err(|e| {
+        error!("Failed to write resolution for {}: {}", file_path, e);
+        JanusError::IoError(format!("Failed to write resolution for {}: {}", file_path, e))
+    })?;
+
+    git::mark_resolved(repo_path, file_path.clone())?;
+
+    info!("Resolved conflict in {}", file_path);
+    Ok(())
+}
@@ -32,6 +32,15 @@ pub enum JanusError {
     #[error("Validation error: {0}")]
     ValidationError(String),
     
+    /// A client-side hook rejected the operation
+    #[error("Hook {hook} failed with exit code {exit_code:?}")]
+    HookFailed {
+        hook: String,
+        exit_code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+    
     /// Unknown or unexpected errors
     #[error("Unknown error: {0}")]
     UnknownError(String),
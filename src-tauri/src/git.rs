@@ -1,6 +1,14 @@
 use crate::error::JanusError;
+use crate::hooks;
+use crate::interactive_rebase;
+use crate::journal;
+use crate::paths;
+use crate::repo_state::{self, RepoState};
+use crate::signing::{self, SignatureInfo, SignatureStatus};
+use crate::worktree;
 use git2::{BranchType, Commit, Repository, Oid, StatusOptions, StatusShow};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use log::{error, info};
@@ -12,6 +20,12 @@ pub struct RepoInfo {
     pub path: String,
     pub name: String,
     pub last_accessed: u64,
+    /// True for a linked worktree created with `git worktree add`
+    #[serde(default)]
+    pub is_worktree: bool,
+    /// Path of the main worktree that a linked worktree belongs to
+    #[serde(default)]
+    pub main_worktree: Option<String>,
 }
 
 impl RepoInfo {
@@ -33,10 +47,18 @@ impl RepoInfo {
             .unwrap_or_default()
             .as_secs();
 
+        // Linked worktrees share the main repository's git directory
+        let main_worktree = Repository::open(&path)
+            .ok()
+            .filter(|repo| repo.is_worktree())
+            .map(|repo| worktree::main_worktree_path(&repo).to_string_lossy().to_string());
+
         Ok(Self {
             path: path.to_string_lossy().to_string(),
             name,
             last_accessed: now,
+            is_worktree: main_worktree.is_some(),
+            main_worktree,
         })
     }
 }
@@ -102,7 +124,8 @@ impl RecentRepos {
 }
 
 /// Get the configuration directory for JanusLens
-fn get_config_dir() -> Result<PathBuf, JanusError> {
+#[cfg(not(test))]
+pub(crate) fn get_config_dir() -> Result<PathBuf, JanusError> {
     let home_dir = dirs::home_dir()
         .ok_or_else(|| JanusError::ConfigError("Could not find home directory".to_string()))?;
     
@@ -118,6 +141,12 @@ fn get_config_dir() -> Result<PathBuf, JanusError> {
     Ok(config_dir)
 }
 
+/// Tests keep their journals and recent repositories out of the user's configuration
+#[cfg(test)]
+pub(crate) fn get_config_dir() -> Result<PathBuf, JanusError> {
+    Ok(std::env::temp_dir().join("januslens_test_config"))
+}
+
 /// List recently opened repositories
 #[tauri::command]
 pub fn list_repositories() -> Result<Vec<RepoInfo>, JanusError> {
@@ -149,8 +178,13 @@ pub fn open_repository(path: String) -> Result<RepoInfo, JanusError> {
             .map_err(|e| JanusError::IoError(format!("Failed to canonicalize path: {}", e)))?
     };
     
+    remember_repository(absolute_path)
+}
+
+/// Adds a repository to the recent repositories and returns its info
+pub(crate) fn remember_repository(path: PathBuf) -> Result<RepoInfo, JanusError> {
     // Create repo info using the helper method
-    let repo_info = RepoInfo::from_path(absolute_path)?;
+    let repo_info = RepoInfo::from_path(path)?;
     
     // Add to recent repositories
     let mut recent_repos = RecentRepos::load()?;
@@ -159,6 +193,36 @@ pub fn open_repository(path: String) -> Result<RepoInfo, JanusError> {
     Ok(repo_info)
 }
 
+/// Creates a new repository, optionally bare or with a given initial branch, and adds it
+/// to recent repositories
+#[tauri::command]
+pub fn init_repository(path: String, bare: Option<bool>, initial_branch: Option<String>) -> Result<RepoInfo, JanusError> {
+    let repo_path = PathBuf::from(&path);
+    if !repo_path.is_absolute() {
+        return Err(JanusError::ValidationError(format!("Repository path {} must be absolute", path)));
+    }
+    if Repository::open(&repo_path).is_ok() {
+        return Err(JanusError::ValidationError(format!("{} is already a git repository", path)));
+    }
+
+    let mut options = git2::RepositoryInitOptions::new();
+    options.bare(bare.unwrap_or(false)).mkpath(true);
+    if let Some(branch) = &initial_branch {
+        if !git2::Reference::is_valid_name(&format!("refs/heads/{}", branch)) {
+            return Err(JanusError::ValidationError(format!("{} is not a valid branch name", branch)));
+        }
+        options.initial_head(branch);
+    }
+
+    Repository::init_opts(&repo_path, &options).map_err(|e| {
+        error!("Failed to initialize repository at {}: {}", path, e);
+        JanusError::GitError(format!("Failed to initialize repository at {}: {}", path, e))
+    })?;
+
+    info!("Initialized repository at {}", path);
+    remember_repository(repo_path)
+}
+
 /// Check if a path is a valid git repository
 #[tauri::command]
 pub fn is_git_repository(path: String) -> Result<bool, JanusError> {
@@ -216,7 +280,7 @@ pub struct BranchInfo {
     pub commit_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CommitInfo {
     pub id: String,
     pub short_id: String,
@@ -238,6 +302,8 @@ pub struct FileStatus {
 pub struct RepoStatus {
     pub staged: Vec<FileStatus>,
     pub unstaged: Vec<FileStatus>,
+    /// Any merge, rebase, cherry-pick, revert or bisect in progress
+    pub state: RepoState,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -246,6 +312,20 @@ pub struct FileEntry {
     pub path: String,
     #[serde(rename = "type")]
     pub type_: String,
+    /// "tracked", "modified", "untracked" or "ignored"
+    pub status: String,
+    /// For directories, whether anything inside is modified or untracked
+    pub has_changes: bool,
+}
+
+/// Filters for `list_files`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ListFilesOptions {
+    /// Leave out files and directories matched by `.gitignore`
+    pub hide_ignored: bool,
+    /// Leave out `.git` directories and files
+    pub hide_git_dir: bool,
 }
 
 /// Gets all branches in the repository
@@ -368,8 +448,47 @@ pub fn get_commits(repo_path: String, branch_name: Option<String>, limit: Option
     Ok(commits)
 }
 
+/// Opens a repository, logging and wrapping any failure
+pub(crate) fn open_repo(repo_path: &str) -> Result<Repository, JanusError> {
+    Repository::open(repo_path).map_err(|e| {
+        error!("Failed to open repository at {}: {}", repo_path, e);
+        JanusError::GitError(format!("Failed to open repository: {}", e))
+    })
+}
+
+/// Collects the unresolved conflicts in an index
+pub(crate) fn index_conflicts(index: &git2::Index) -> Result<Vec<git2::IndexConflict>, JanusError> {
+    let mut conflicts = Vec::new();
+    for entry in index.conflicts().map_err(|e| {
+        error!("Failed to get conflict information: {}", e);
+        JanusError::GitError(format!("Failed to get conflict information: {}", e))
+    })? {
+        let entry = entry.map_err(|e| {
+            error!("Failed to get conflict entry: {}", e);
+            JanusError::GitError(format!("Failed to get conflict entry: {}", e))
+        })?;
+        conflicts.push(entry);
+    }
+    
+    Ok(conflicts)
+}
+
+/// Gets the path of a conflict.
+/// A side is missing when one branch deleted the file.
+pub(crate) fn conflict_path(conflict: &git2::IndexConflict) -> Option<String> {
+    conflict.our.as_ref()
+        .or(conflict.their.as_ref())
+        .or(conflict.ancestor.as_ref())
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+}
+
+/// Lists the paths with unresolved conflicts in an index
+pub(crate) fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>, JanusError> {
+    Ok(index_conflicts(index)?.iter().filter_map(conflict_path).collect())
+}
+
 // Helper function to convert a git2::Commit to our CommitInfo
-fn commit_to_info(commit: &Commit) -> Result<CommitInfo, JanusError> {
+pub(crate) fn commit_to_info(commit: &Commit) -> Result<CommitInfo, JanusError> {
     let id = commit.id().to_string();
     let short_id = id.chars().take(8).collect();
     
@@ -496,16 +615,19 @@ pub fn get_status(repo_path: String) -> Result<RepoStatus, JanusError> {
         }
     }
     
-    Ok(RepoStatus { staged, unstaged })
+    let state = repo_state::repository_state(&repo)?;
+    
+    Ok(RepoStatus { staged, unstaged, state })
 }
 
 /// Gets the diff for a specific file
 #[tauri::command]
 pub fn get_diff(repo_path: String, file_path: String, staged: bool) -> Result<String, JanusError> {
-    let _repo = Repository::open(&repo_path).map_err(|e| {
+    let repo = Repository::open(&repo_path).map_err(|e| {
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
     
     // For simplicity, we'll use git command line to get the diff
     // In a real implementation, you would use git2's diff functionality
@@ -543,14 +665,15 @@ pub fn get_diff(repo_path: String, file_path: String, staged: bool) -> Result<St
 /// Stages a file
 #[tauri::command]
 pub fn stage_file(repo_path: String, file_path: String) -> Result<(), JanusError> {
-    let _repo = Repository::open(&repo_path).map_err(|e| {
+    let repo = Repository::open(&repo_path).map_err(|e| {
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
     
     // Use git command line for simplicity
     let output = std::process::Command::new("git")
-        .args(&["add", &file_path])
+        .args(&["add", "--", &file_path])
         .current_dir(&repo_path)
         .output()
         .map_err(|e| {
@@ -570,10 +693,11 @@ pub fn stage_file(repo_path: String, file_path: String) -> Result<(), JanusError
 /// Unstages a file
 #[tauri::command]
 pub fn unstage_file(repo_path: String, file_path: String) -> Result<(), JanusError> {
-    let _repo = Repository::open(&repo_path).map_err(|e| {
+    let repo = Repository::open(&repo_path).map_err(|e| {
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
     
     // Use git command line for simplicity
     let output = std::process::Command::new("git")
@@ -594,42 +718,333 @@ pub fn unstage_file(repo_path: String, file_path: String) -> Result<(), JanusErr
     Ok(())
 }
 
-/// Creates a commit
+/// An author that can be credited on a commit
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AuthorInfo {
+    pub name: String,
+    pub email: String,
+}
+
+/// Options controlling how a commit is created
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CommitOptions {
+    /// Append a `Signed-off-by` trailer for the configured user
+    pub signoff: bool,
+    /// Authors credited with `Co-authored-by` trailers
+    pub co_authors: Vec<AuthorInfo>,
+    /// Allow a commit that records no changes
+    pub allow_empty: bool,
+    /// Skip the `pre-commit` and `commit-msg` hooks
+    pub no_verify: bool,
+}
+
+/// Appends trailers to a commit message, skipping any that are already present
+pub(crate) fn append_trailers(message: &str, trailers: &[String]) -> String {
+    let mut result = message.trim_end().to_string();
+    let trailers: Vec<&String> = trailers
+        .iter()
+        .filter(|t| !result.lines().any(|line| line.trim() == t.as_str()))
+        .collect();
+    
+    if trailers.is_empty() {
+        return result;
+    }
+    
+    // Trailers belong in the last paragraph; start a new one unless it already holds trailers
+    let last_paragraph = result.rsplit("\n\n").next().unwrap_or("");
+    let trailer_re = regex::Regex::new(r"^[A-Za-z0-9-]+: ").expect("valid trailer regex");
+    let has_trailer_block = result.contains("\n\n")
+        && last_paragraph.lines().all(|line| trailer_re.is_match(line));
+    
+    result.push_str(if has_trailer_block { "\n" } else { "\n\n" });
+    result.push_str(&trailers.iter().map(|t| t.as_str()).collect::<Vec<_>>().join("\n"));
+    result
+}
+
+/// Lists distinct authors from the history of HEAD, most recent first
 #[tauri::command]
-pub fn create_commit(repo_path: String, message: String) -> Result<CommitInfo, JanusError> {
-    let repo = Repository::open(&repo_path).map_err(|e| {
-        error!("Failed to open repository at {}: {}", repo_path, e);
-        JanusError::GitError(format!("Failed to open repository: {}", e))
+pub fn get_known_authors(repo_path: String, limit: Option<u32>) -> Result<Vec<AuthorInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    
+    let mut revwalk = repo.revwalk().map_err(|e| {
+        error!("Failed to create revision walker: {}", e);
+        JanusError::GitError(format!("Failed to create revision walker: {}", e))
     })?;
     
-    // Use git command line for simplicity
-    let output = std::process::Command::new("git")
-        .args(&["commit", "-m", &message])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| {
-            error!("Failed to execute git commit command: {}", e);
-            JanusError::IoError(format!("Failed to execute git commit command: {}", e))
+    // An unborn HEAD simply has no authors yet
+    if revwalk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    
+    let limit = limit.unwrap_or(1000) as usize;
+    let mut authors: Vec<AuthorInfo> = Vec::new();
+    
+    for oid_result in revwalk.take(limit) {
+        let oid = oid_result.map_err(|e| {
+            error!("Failed to get commit ID: {}", e);
+            JanusError::GitError(format!("Failed to get commit ID: {}", e))
         })?;
+        
+        let commit = repo.find_commit(oid).map_err(|e| {
+            error!("Failed to find commit {}: {}", oid, e);
+            JanusError::GitError(format!("Failed to find commit {}: {}", oid, e))
+        })?;
+        
+        let author = commit.author();
+        let email = author.email().unwrap_or("").to_string();
+        if email.is_empty() || authors.iter().any(|a| a.email == email) {
+            continue;
+        }
+        
+        authors.push(AuthorInfo {
+            name: author.name().unwrap_or("Unknown").to_string(),
+            email,
+        });
+    }
     
-    if !output.status.success() {
-        let error_message = String::from_utf8_lossy(&output.stderr).to_string();
-        error!("Git commit command failed: {}", error_message);
-        return Err(JanusError::GitError(format!("Git commit command failed: {}", error_message)));
+    Ok(authors)
+}
+
+/// Fails while an operation that concludes with its own commit is stopped, since a plain
+/// commit would drop the merge parents or the picked commit's authorship
+fn ensure_can_commit(repo: &Repository) -> Result<(), JanusError> {
+    interactive_rebase::ensure_not_in_progress(repo)?;
+    let command = match repo.state() {
+        git2::RepositoryState::Clean | git2::RepositoryState::Bisect => return Ok(()),
+        git2::RepositoryState::Merge => "merge_continue",
+        git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence => "cherry_pick_continue",
+        git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence => "revert_continue",
+        _ => "rebase_continue",
+    };
+    Err(JanusError::ValidationError(format!(
+        "Cannot commit while another operation is in progress. Use {} to conclude it, or abort it first.",
+        command
+    )))
+}
+
+/// Creates a commit
+#[tauri::command]
+pub fn create_commit(repo_path: String, message: String, options: Option<CommitOptions>) -> Result<CommitInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    ensure_can_commit(&repo)?;
+    let _journal = journal::Recorder::begin(&repo, "create_commit", &format!("Commit \"{}\"", message.lines().next().unwrap_or("")), ResetMode::Soft);
+    let options = options.unwrap_or_default();
+    
+    // Build the trailers requested by the options
+    let mut trailers = Vec::new();
+    if options.signoff {
+        let signature = repo.signature().map_err(|e| {
+            error!("Failed to get signature: {}", e);
+            JanusError::GitError(format!("Failed to get signature: {}", e))
+        })?;
+        trailers.push(format!(
+            "Signed-off-by: {} <{}>",
+            signature.name().unwrap_or("Unknown"),
+            signature.email().unwrap_or("")
+        ));
+    }
+    for co_author in &options.co_authors {
+        trailers.push(format!("Co-authored-by: {} <{}>", co_author.name, co_author.email));
     }
+    let message = append_trailers(&message, &trailers);
     
-    // Get the created commit
-    let head = repo.head().map_err(|e| {
-        error!("Failed to get HEAD reference: {}", e);
-        JanusError::GitError(format!("Failed to get HEAD reference: {}", e))
+    if !options.no_verify {
+        hooks::pre_commit(&repo)?;
+    }
+    
+    // The commit-msg hook may rewrite the message file in place
+    let message_path = repo.path().join("COMMIT_EDITMSG");
+    fs::write(&message_path, &message)
+        .map_err(|e| JanusError::IoError(format!("Failed to write commit message: {}", e)))?;
+    
+    if !options.no_verify {
+        hooks::commit_msg(&repo, &message_path)?;
+    }
+    
+    // Re-read the message in case commit-msg changed it
+    let message = fs::read_to_string(&message_path)
+        .map_err(|e| JanusError::IoError(format!("Failed to read commit message: {}", e)))?;
+    let message = git2::message_prettify(&message, None).map_err(|e| {
+        error!("Failed to clean up commit message: {}", e);
+        JanusError::GitError(format!("Failed to clean up commit message: {}", e))
     })?;
+    if message.trim().is_empty() {
+        return Err(JanusError::ValidationError("Aborting commit due to empty commit message".to_string()));
+    }
     
-    let head_commit = head.peel_to_commit().map_err(|e| {
-        error!("Failed to peel HEAD to commit: {}", e);
-        JanusError::GitError(format!("Failed to peel HEAD to commit: {}", e))
+    let mut index = repo.index().map_err(|e| {
+        error!("Failed to get repository index: {}", e);
+        JanusError::GitError(format!("Failed to get repository index: {}", e))
+    })?;
+    
+    if index.has_conflicts() {
+        return Err(JanusError::GitError("Cannot commit while there are unresolved conflicts".to_string()));
+    }
+    
+    let tree_id = index.write_tree().map_err(|e| {
+        error!("Failed to write tree: {}", e);
+        JanusError::GitError(format!("Failed to write tree: {}", e))
+    })?;
+    
+    let tree = repo.find_tree(tree_id).map_err(|e| {
+        error!("Failed to find tree: {}", e);
+        JanusError::GitError(format!("Failed to find tree: {}", e))
+    })?;
+    
+    // An unborn branch has no parent commit
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().map_err(|e| {
+            error!("Failed to peel HEAD to commit: {}", e);
+            JanusError::GitError(format!("Failed to peel HEAD to commit: {}", e))
+        })?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => {
+            error!("Failed to get HEAD reference: {}", e);
+            return Err(JanusError::GitError(format!("Failed to get HEAD reference: {}", e)));
+        }
+    };
+    
+    let unchanged = match &parent {
+        Some(parent) => parent.tree_id() == tree_id,
+        None => tree.is_empty(),
+    };
+    if unchanged && !options.allow_empty {
+        return Err(JanusError::GitError("Nothing to commit".to_string()));
+    }
+    
+    let signature = repo.signature().map_err(|e| {
+        error!("Failed to get signature: {}", e);
+        JanusError::GitError(format!("Failed to get signature: {}", e))
     })?;
     
-    commit_to_info(&head_commit)
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let commit_id = write_commit(&repo, &signature, &signature, &message, &tree, &parents)?;
+    
+    hooks::run_notify_hook(&repo, "post-commit", &[]);
+    
+    let commit = repo.find_commit(commit_id).map_err(|e| {
+        error!("Failed to find commit {}: {}", commit_id, e);
+        JanusError::GitError(format!("Failed to find commit {}: {}", commit_id, e))
+    })?;
+    
+    commit_to_info(&commit)
+}
+
+/// Writes a commit and advances HEAD to it, signing it if `commit.gpgsign` is set
+pub(crate) fn write_commit(
+    repo: &Repository,
+    author: &git2::Signature,
+    committer: &git2::Signature,
+    message: &str,
+    tree: &git2::Tree,
+    parents: &[&Commit],
+) -> Result<Oid, JanusError> {
+    let commit_id = write_commit_object(repo, author, committer, message, tree, parents)?;
+    
+    let summary = message.lines().next().unwrap_or("");
+    let reflog_message = if parents.is_empty() {
+        format!("commit (initial): {}", summary)
+    } else {
+        format!("commit: {}", summary)
+    };
+    update_head(repo, commit_id, &reflog_message)?;
+    
+    Ok(commit_id)
+}
+
+/// Writes a commit object, signed if `commit.gpgsign` is set, without moving any ref
+pub(crate) fn write_commit_object(
+    repo: &Repository,
+    author: &git2::Signature,
+    committer: &git2::Signature,
+    message: &str,
+    tree: &git2::Tree,
+    parents: &[&Commit],
+) -> Result<Oid, JanusError> {
+    if !signing::should_sign_commits(repo) {
+        return repo.commit(None, author, committer, message, tree, parents).map_err(|e| {
+            error!("Failed to create commit: {}", e);
+            JanusError::GitError(format!("Failed to create commit: {}", e))
+        });
+    }
+    
+    let buffer = repo.commit_create_buffer(author, committer, message, tree, parents).map_err(|e| {
+        error!("Failed to create commit buffer: {}", e);
+        JanusError::GitError(format!("Failed to create commit buffer: {}", e))
+    })?;
+    let buffer = std::str::from_utf8(&buffer)
+        .map_err(|e| JanusError::ParseError(format!("Commit buffer is not valid UTF-8: {}", e)))?;
+    
+    let signature = signing::sign_buffer(repo, buffer)?;
+    repo.commit_signed(buffer, &signature, None).map_err(|e| {
+        error!("Failed to create signed commit: {}", e);
+        JanusError::GitError(format!("Failed to create signed commit: {}", e))
+    })
+}
+
+/// Points HEAD, or the branch it refers to, at the given commit
+pub(crate) fn update_head(repo: &Repository, oid: Oid, reflog_message: &str) -> Result<(), JanusError> {
+    let head = repo.find_reference("HEAD").map_err(|e| {
+        error!("Failed to find HEAD: {}", e);
+        JanusError::GitError(format!("Failed to find HEAD: {}", e))
+    })?;
+    
+    match head.symbolic_target() {
+        Some(branch_ref) => {
+            repo.reference(branch_ref, oid, true, reflog_message).map_err(|e| {
+                error!("Failed to update {}: {}", branch_ref, e);
+                JanusError::GitError(format!("Failed to update {}: {}", branch_ref, e))
+            })?;
+        }
+        None => {
+            repo.set_head_detached(oid).map_err(|e| {
+                error!("Failed to update HEAD: {}", e);
+                JanusError::GitError(format!("Failed to update HEAD: {}", e))
+            })?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Verifies the signature of a commit
+#[tauri::command]
+pub fn get_commit_signature(repo_path: String, commit_id: String) -> Result<SignatureInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    
+    let commit = repo.revparse_single(&commit_id)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| {
+            error!("Failed to find commit {}: {}", commit_id, e);
+            JanusError::GitError(format!("Failed to find commit {}: {}", commit_id, e))
+        })?;
+    
+    signing::verify_commit(&repo, commit.id())
+}
+
+/// Verifies the signature of an annotated tag
+#[tauri::command]
+pub fn get_tag_signature(repo_path: String, tag_name: String) -> Result<SignatureInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    
+    let reference = repo.find_reference(&format!("refs/tags/{}", tag_name)).map_err(|e| {
+        error!("Failed to find tag {}: {}", tag_name, e);
+        JanusError::GitError(format!("Failed to find tag {}: {}", tag_name, e))
+    })?;
+    
+    // Lightweight tags point straight at a commit and cannot be signed
+    let tag_id = reference.peel_to_tag().map(|tag| tag.id());
+    match tag_id {
+        Ok(tag_id) => signing::verify_tag(&repo, tag_id),
+        Err(_) => Ok(SignatureInfo {
+            status: SignatureStatus::Unsigned,
+            format: None,
+            signer: None,
+            key: None,
+            details: "Lightweight tags cannot be signed".to_string(),
+        }),
+    }
 }
 
 /// Creates a new branch
@@ -639,6 +1054,7 @@ pub fn create_branch(repo_path: String, branch_name: String) -> Result<BranchInf
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    let _journal = journal::Recorder::begin(&repo, "create_branch", &format!("Create branch {}", branch_name), ResetMode::Soft);
     
     // Get HEAD commit to create branch from
     let head = repo.head().map_err(|e| {
@@ -674,6 +1090,7 @@ pub fn delete_branch(repo_path: String, branch_name: String) -> Result<(), Janus
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    let _journal = journal::Recorder::begin(&repo, "delete_branch", &format!("Delete branch {}", branch_name), ResetMode::Soft);
     
     // Find the branch
     let mut branch = repo.find_branch(&branch_name, BranchType::Local).map_err(|e| {
@@ -690,12 +1107,19 @@ pub fn delete_branch(repo_path: String, branch_name: String) -> Result<(), Janus
     Ok(())
 }
 
-/// Lists files in the repository
+/// Every directory containing a worktree path, as prefixes with a trailing slash
+fn dir_prefixes(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/').map(move |(end, _)| &path[..=end])
+}
+
+/// Lists files in the repository, annotated with their git status
 #[tauri::command]
-pub fn list_files(repo_path: String, directory: Option<String>) -> Result<Vec<FileEntry>, JanusError> {
+pub fn list_files(repo_path: String, directory: Option<String>, options: Option<ListFilesOptions>) -> Result<Vec<FileEntry>, JanusError> {
+    let options = options.unwrap_or_default();
+    let repo = open_repo(&repo_path)?;
     let repo_path_buf = PathBuf::from(&repo_path);
     let target_dir = match directory {
-        Some(dir) => repo_path_buf.join(dir),
+        Some(dir) => repo_path_buf.join(paths::validate_dir_path(&repo, &dir)?),
         None => repo_path_buf.clone(),
     };
     
@@ -703,6 +1127,26 @@ pub fn list_files(repo_path: String, directory: Option<String>) -> Result<Vec<Fi
         return Err(JanusError::IoError(format!("Directory does not exist: {:?}", target_dir)));
     }
     
+    // Everything that differs from HEAD, keyed by path relative to the worktree
+    let mut status_options = StatusOptions::new();
+    status_options.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repo.statuses(Some(&mut status_options)).map_err(|e| {
+        error!("Failed to get repository status: {}", e);
+        JanusError::GitError(format!("Failed to get repository status: {}", e))
+    })?;
+    let changes: HashMap<String, git2::Status> = statuses.iter()
+        .filter(|entry| !entry.status().is_ignored())
+        .filter_map(|entry| entry.path().map(|path| (path.to_string(), entry.status())))
+        .collect();
+    let changed_dirs: HashSet<&str> = changes.keys().flat_map(|path| dir_prefixes(path)).collect();
+    
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let index_paths: Vec<String> = index.iter()
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect();
+    let tracked_dirs: HashSet<&str> = index_paths.iter().flat_map(|path| dir_prefixes(path)).collect();
+    
     let mut entries = Vec::new();
     
     for entry in std::fs::read_dir(&target_dir).map_err(|e| {
@@ -720,21 +1164,49 @@ pub fn list_files(repo_path: String, directory: Option<String>) -> Result<Vec<Fi
             .to_string_lossy()
             .to_string();
         
-        let file_type = if path.is_dir() {
+        let name = path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        
+        if options.hide_git_dir && name == ".git" {
+            continue;
+        }
+        
+        let is_dir = path.is_dir();
+        let file_type = if is_dir {
             "directory".to_string()
         } else {
             "file".to_string()
         };
         
-        let name = path.file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
+        // A trailing slash makes directory-only rules such as `build/` match
+        let ignore_path = if is_dir { format!("{}/", relative_path) } else { relative_path.clone() };
+        let ignored = name == ".git" || repo.status_should_ignore(Path::new(&ignore_path)).unwrap_or(false);
+        if options.hide_ignored && ignored {
+            continue;
+        }
+        
+        let (status, has_changes) = if ignored {
+            ("ignored", false)
+        } else if is_dir {
+            let prefix = format!("{}/", relative_path);
+            let tracked = tracked_dirs.contains(prefix.as_str());
+            (if tracked { "tracked" } else { "untracked" }, changed_dirs.contains(prefix.as_str()))
+        } else {
+            match changes.get(&relative_path) {
+                Some(status) if status.is_wt_new() => ("untracked", true),
+                Some(_) => ("modified", true),
+                None => ("tracked", false),
+            }
+        };
         
         entries.push(FileEntry {
             name,
             path: relative_path,
             type_: file_type,
+            status: status.to_string(),
+            has_changes,
         });
     }
     
@@ -759,6 +1231,8 @@ pub fn checkout_branch(repo_path: String, branch_name: String) -> Result<(), Jan
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    interactive_rebase::ensure_not_in_progress(&repo)?;
+    let _journal = journal::Recorder::begin(&repo, "checkout_branch", &format!("Check out {}", branch_name), ResetMode::Hard);
     
     // Find the branch
     let branch = repo.find_branch(&branch_name, BranchType::Local).map_err(|e| {
@@ -768,6 +1242,7 @@ pub fn checkout_branch(repo_path: String, branch_name: String) -> Result<(), Jan
     
     // Get the branch reference
     let branch_ref = branch.get();
+    let old_head = repo.head().ok().and_then(|h| h.target()).unwrap_or_else(Oid::zero);
     
     // Checkout the branch
     let obj = branch_ref.peel(git2::ObjectType::Any).map_err(|e| {
@@ -786,10 +1261,111 @@ pub fn checkout_branch(repo_path: String, branch_name: String) -> Result<(), Jan
         JanusError::GitError(format!("Failed to set HEAD: {}", e))
     })?;
     
+    let new_head = branch_ref.target().unwrap_or_else(Oid::zero);
+    hooks::post_checkout(&repo, old_head, new_head, true);
+    
     info!("Successfully checked out branch: {}", branch_name);
     Ok(())
 }
 
+/// How far a reset reaches, mirroring `git reset --soft/--mixed/--hard`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetMode {
+    Soft,
+    Mixed,
+    Hard,
+}
+
+impl From<ResetMode> for git2::ResetType {
+    fn from(mode: ResetMode) -> Self {
+        match mode {
+            ResetMode::Soft => git2::ResetType::Soft,
+            ResetMode::Mixed => git2::ResetType::Mixed,
+            ResetMode::Hard => git2::ResetType::Hard,
+        }
+    }
+}
+
+/// Struct to represent reset results
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetResult {
+    pub previous_head: String,
+    pub new_head: String,
+    pub snapshot: Option<String>,
+}
+
+/// Moves the current branch to a revision.
+/// Hard resets first save uncommitted changes as a snapshot so they can be recovered.
+#[tauri::command]
+pub fn reset_to(repo_path: String, revspec: String, mode: ResetMode) -> Result<ResetResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    interactive_rebase::ensure_not_in_progress(&repo)?;
+
+    let previous_head = repo.head().and_then(|h| h.peel_to_commit()).map_err(|e| {
+        error!("Failed to resolve HEAD: {}", e);
+        JanusError::GitError(format!("Failed to resolve HEAD: {}", e))
+    })?;
+
+    let target = repo.revparse_single(&revspec).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to resolve {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to resolve {}: {}", revspec, e))
+    })?;
+
+    let mut journal = journal::Recorder::begin(&repo, "reset_to", &format!("Reset to {}", revspec), mode);
+    let snapshot = if mode == ResetMode::Hard {
+        crate::snapshot::create_snapshot(&repo, &format!("reset --hard {}", revspec))?
+    } else {
+        None
+    };
+    journal.set_snapshot(snapshot);
+
+    repo.reset(target.as_object(), mode.into(), None).map_err(|e| {
+        error!("Failed to reset to {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to reset to {}: {}", revspec, e))
+    })?;
+
+    info!("Reset ({:?}) from {} to {}", mode, previous_head.id(), target.id());
+    Ok(ResetResult {
+        previous_head: previous_head.id().to_string(),
+        new_head: target.id().to_string(),
+        snapshot: snapshot.map(|id| id.to_string()),
+    })
+}
+
+/// How a merge may be carried out, mirroring `git merge --ff-only/--no-ff/--squash`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Fast-forward when possible, otherwise create a merge commit; honours `merge.ff`
+    #[default]
+    Auto,
+    FastForwardOnly,
+    NoFastForward,
+    /// Stage the merged changes without committing or recording a merge
+    Squash,
+}
+
+/// Options for merging a branch
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MergeOptions {
+    pub mode: MergeMode,
+    /// Message for the merge commit instead of the default "Merge branch ..." text
+    pub message: Option<String>,
+}
+
+/// Which path a merge took
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeOutcome {
+    UpToDate,
+    FastForward,
+    MergeCommit,
+    Squashed,
+    Conflicts,
+}
+
 /// Struct to represent merge results
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MergeResult {
@@ -797,15 +1373,197 @@ pub struct MergeResult {
     pub has_conflicts: bool,
     pub message: String,
     pub conflicted_files: Vec<String>,
+    pub outcome: MergeOutcome,
+    /// The commit HEAD points at after the merge
+    pub commit_id: Option<String>,
+}
+
+/// Moves the current branch forward to `target` without creating a commit
+fn fast_forward(repo: &Repository, head: &git2::Reference, target: &Commit, source_branch: &str) -> Result<(), JanusError> {
+    let mut checkout_options = git2::build::CheckoutBuilder::new();
+    checkout_options.safe();
+    repo.checkout_tree(target.as_object(), Some(&mut checkout_options)).map_err(|e| {
+        error!("Failed to checkout {}: {}", target.id(), e);
+        JanusError::GitError(format!("Failed to checkout {}: {}", target.id(), e))
+    })?;
+    
+    let branch_ref = head.name().unwrap_or("HEAD");
+    repo.reference(branch_ref, target.id(), true, &format!("merge {}: Fast-forward", source_branch)).map_err(|e| {
+        error!("Failed to fast-forward {}: {}", branch_ref, e);
+        JanusError::GitError(format!("Failed to fast-forward {}: {}", branch_ref, e))
+    })?;
+    
+    Ok(())
+}
+
+/// Builds the `SQUASH_MSG` text listing the commits being squashed, like `git merge --squash`
+fn squash_message(repo: &Repository, head: Oid, source: Oid) -> Result<String, JanusError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(source)?;
+    revwalk.hide(head)?;
+    
+    let mut message = String::from("Squashed commit of the following:\n");
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let author = commit.author();
+        message.push_str(&format!(
+            "\ncommit {}\nAuthor: {} <{}>\n\n",
+            commit.id(),
+            author.name().unwrap_or(""),
+            author.email().unwrap_or("")
+        ));
+        for line in commit.message().unwrap_or("").lines() {
+            message.push_str(&format!("    {}\n", line));
+        }
+    }
+    
+    Ok(message)
+}
+
+/// Predicted result of merging a branch
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePreviewOutcome {
+    UpToDate,
+    FastForward,
+    Clean,
+    Conflicts,
+}
+
+/// Dry run of a merge, computed without touching the index or working tree
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergePreview {
+    pub outcome: MergePreviewOutcome,
+    pub fast_forward_possible: bool,
+    pub conflicted_files: Vec<String>,
+    /// Files the merge changes relative to HEAD
+    pub changed_files: Vec<FileStatus>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Uncommitted changes that stop the merge: anything staged, or working tree files the merge touches
+    pub blocking_files: Vec<String>,
+}
+
+fn delta_status(delta: git2::Delta) -> &'static str {
+    match delta {
+        git2::Delta::Added | git2::Delta::Copied => "new",
+        git2::Delta::Deleted => "deleted",
+        git2::Delta::Renamed => "renamed",
+        git2::Delta::Typechange => "typechange",
+        git2::Delta::Conflicted => "conflicted",
+        _ => "modified",
+    }
+}
+
+/// Merges `source` into HEAD in memory and compares the result with the uncommitted changes
+fn compute_merge_preview(repo: &Repository, source: &Commit) -> Result<MergePreview, JanusError> {
+    let head_commit = repo.head().and_then(|h| h.peel_to_commit()).map_err(|e| {
+        error!("Failed to get HEAD commit: {}", e);
+        JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
+    })?;
+    let head_tree = head_commit.tree()?;
+    
+    let annotated_commit = repo.find_annotated_commit(source.id())?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated_commit]).map_err(|e| {
+        error!("Failed to analyze merge: {}", e);
+        JanusError::GitError(format!("Failed to analyze merge: {}", e))
+    })?;
+    
+    let (outcome, diff, conflicted_files) = if analysis.is_up_to_date() {
+        (MergePreviewOutcome::UpToDate, None, Vec::new())
+    } else if analysis.is_fast_forward() {
+        let diff = repo.diff_tree_to_tree(Some(&head_tree), Some(&source.tree()?), None)?;
+        (MergePreviewOutcome::FastForward, Some(diff), Vec::new())
+    } else {
+        let merged = repo.merge_commits(&head_commit, source, None).map_err(|e| {
+            error!("Failed to merge in memory: {}", e);
+            JanusError::GitError(format!("Failed to merge in memory: {}", e))
+        })?;
+        let conflicted_files = conflicted_paths(&merged)?;
+        let diff = repo.diff_tree_to_index(Some(&head_tree), Some(&merged), None)?;
+        let outcome = if conflicted_files.is_empty() {
+            MergePreviewOutcome::Clean
+        } else {
+            MergePreviewOutcome::Conflicts
+        };
+        (outcome, Some(diff), conflicted_files)
+    };
+    
+    let mut changed_files: Vec<FileStatus> = Vec::new();
+    let (mut insertions, mut deletions) = (0, 0);
+    if let Some(diff) = &diff {
+        for delta in diff.deltas() {
+            let path = delta.new_file().path().or(delta.old_file().path());
+            let Some(path) = path.map(|p| p.to_string_lossy().to_string()) else { continue };
+            if !changed_files.iter().any(|f| f.path == path) {
+                changed_files.push(FileStatus { path, status: delta_status(delta.status()).to_string() });
+            }
+        }
+        let stats = diff.stats()?;
+        insertions = stats.insertions();
+        deletions = stats.deletions();
+    }
+    
+    let mut blocking_files = Vec::new();
+    if outcome != MergePreviewOutcome::UpToDate {
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let statuses = repo.statuses(Some(&mut options)).map_err(|e| {
+            error!("Failed to get repository status: {}", e);
+            JanusError::GitError(format!("Failed to get repository status: {}", e))
+        })?;
+        
+        let touched = |path: &str| {
+            changed_files.iter().any(|f| f.path == path) || conflicted_files.iter().any(|f| f == path)
+        };
+        let staged = git2::Status::INDEX_NEW | git2::Status::INDEX_MODIFIED | git2::Status::INDEX_DELETED
+            | git2::Status::INDEX_RENAMED | git2::Status::INDEX_TYPECHANGE;
+        
+        for entry in statuses.iter() {
+            let Some(path) = entry.path() else { continue };
+            // The merge commit is written from the index, so staged changes would leak into it
+            if entry.status().intersects(staged) || touched(path) {
+                blocking_files.push(path.to_string());
+            }
+        }
+    }
+    
+    Ok(MergePreview {
+        outcome,
+        fast_forward_possible: analysis.is_fast_forward(),
+        conflicted_files,
+        changed_files,
+        insertions,
+        deletions,
+        blocking_files,
+    })
+}
+
+/// Predicts the outcome of merging a branch without touching the working tree
+#[tauri::command]
+pub fn preview_merge(repo_path: String, source_branch: String) -> Result<MergePreview, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    
+    let source_commit = repo.revparse_single(&source_branch)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| {
+            error!("Failed to find {}: {}", source_branch, e);
+            JanusError::GitError(format!("Failed to find {}: {}", source_branch, e))
+        })?;
+    
+    compute_merge_preview(&repo, &source_commit)
 }
 
 /// Merges a source branch into the current branch
 #[tauri::command]
-pub fn merge_branch(repo_path: String, source_branch: String) -> Result<MergeResult, JanusError> {
+pub fn merge_branch(repo_path: String, source_branch: String, options: Option<MergeOptions>) -> Result<MergeResult, JanusError> {
+    let options = options.unwrap_or_default();
     let repo = Repository::open(&repo_path).map_err(|e| {
         error!("Failed to open repository at {}: {}", repo_path, e);
         JanusError::GitError(format!("Failed to open repository: {}", e))
     })?;
+    interactive_rebase::ensure_not_in_progress(&repo)?;
+    let _journal = journal::Recorder::begin(&repo, "merge_branch", &format!("Merge {}", source_branch), ResetMode::Hard);
     
     // Get the current branch (destination)
     let head = repo.head().map_err(|e| {
@@ -820,6 +1578,11 @@ pub fn merge_branch(repo_path: String, source_branch: String) -> Result<MergeRes
     let head_name = head.shorthand().unwrap_or("unknown");
     info!("Current branch (destination): {}", head_name);
     
+    let head_commit = head.peel_to_commit().map_err(|e| {
+        error!("Failed to get HEAD commit: {}", e);
+        JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
+    })?;
+    
     // Get the source branch
     let source_branch_ref = repo.find_branch(&source_branch, BranchType::Local).map_err(|e| {
         error!("Failed to find branch {}: {}", source_branch, e);
@@ -833,22 +1596,15 @@ pub fn merge_branch(repo_path: String, source_branch: String) -> Result<MergeRes
     
     info!("Source branch: {}", source_branch);
 
-    // Check if there are uncommitted changes
-    let statuses = repo.statuses(None).map_err(|e| {
-        error!("Failed to get repository status: {}", e);
-        JanusError::GitError(format!("Failed to get repository status: {}", e))
-    })?;
-    
-    if statuses.iter().any(|s| s.status() != git2::Status::CURRENT) {
-        return Err(JanusError::GitError(
-            "There are uncommitted changes. Please commit or stash them before merging.".to_string()
-        ));
+    // Uncommitted changes only block the merge when it would touch them
+    let preview = compute_merge_preview(&repo, &source_commit)?;
+    if !preview.blocking_files.is_empty() {
+        return Err(JanusError::GitError(format!(
+            "Uncommitted changes would be affected by the merge: {}. Please commit or stash them before merging.",
+            preview.blocking_files.join(", ")
+        )));
     }
     
-    // Prepare checkout options
-    let mut checkout_options = git2::build::CheckoutBuilder::new();
-    checkout_options.allow_conflicts(true);
-    
     // Create an annotated commit from the branch ref for merging
     let annotated_commit = repo.reference_to_annotated_commit(&source_branch_ref.into_reference())
         .map_err(|e| {
@@ -856,103 +1612,381 @@ pub fn merge_branch(repo_path: String, source_branch: String) -> Result<MergeRes
             JanusError::GitError(format!("Failed to create annotated commit: {}", e))
         })?;
     
+    let (analysis, preference) = repo.merge_analysis(&[&annotated_commit]).map_err(|e| {
+        error!("Failed to analyze merge: {}", e);
+        JanusError::GitError(format!("Failed to analyze merge: {}", e))
+    })?;
+    
+    // merge.ff only applies when the caller did not ask for a mode
+    let mode = match options.mode {
+        MergeMode::Auto if preference.is_no_fast_forward() => MergeMode::NoFastForward,
+        MergeMode::Auto if preference.is_fastforward_only() => MergeMode::FastForwardOnly,
+        mode => mode,
+    };
+    
+    if analysis.is_up_to_date() {
+        return Ok(MergeResult {
+            success: true,
+            has_conflicts: false,
+            message: format!("'{}' is already up to date with '{}'", head_name, source_branch),
+            conflicted_files: Vec::new(),
+            outcome: MergeOutcome::UpToDate,
+            commit_id: Some(head_commit.id().to_string()),
+        });
+    }
+    
+    if mode == MergeMode::FastForwardOnly && !analysis.is_fast_forward() {
+        return Err(JanusError::GitError(format!(
+            "Cannot fast-forward '{}' to '{}': the branches have diverged", head_name, source_branch
+        )));
+    }
+    
+    if analysis.is_fast_forward() && matches!(mode, MergeMode::Auto | MergeMode::FastForwardOnly) {
+        fast_forward(&repo, &head, &source_commit, &source_branch)?;
+        hooks::post_merge(&repo, false);
+        
+        info!("Fast-forwarded '{}' to '{}'", head_name, source_branch);
+        return Ok(MergeResult {
+            success: true,
+            has_conflicts: false,
+            message: format!("Fast-forwarded '{}' to '{}'", head_name, source_branch),
+            conflicted_files: Vec::new(),
+            outcome: MergeOutcome::FastForward,
+            commit_id: Some(source_commit.id().to_string()),
+        });
+    }
+    
+    // Prepare checkout options
+    let mut checkout_options = git2::build::CheckoutBuilder::new();
+    checkout_options.allow_conflicts(true);
+    
     // Perform the merge
-    match repo.merge(&[&annotated_commit], None, Some(&mut checkout_options)) {
-        Ok(_) => {
-            // Check for conflicts
-            let mut index = repo.index().map_err(|e| {
-                error!("Failed to get repository index: {}", e);
-                JanusError::GitError(format!("Failed to get repository index: {}", e))
-            })?;
-            
-            if index.has_conflicts() {
-                info!("Merge has conflicts that need to be resolved");
-                
-                // Get conflicted files
-                let mut conflicted_files = Vec::new();
-                for entry in index.conflicts().map_err(|e| {
-                    error!("Failed to get conflict information: {}", e);
-                    JanusError::GitError(format!("Failed to get conflict information: {}", e))
-                })? {
-                    let entry = entry.map_err(|e| {
-                        error!("Failed to get conflict entry: {}", e);
-                        JanusError::GitError(format!("Failed to get conflict entry: {}", e))
-                    })?;
-                    
-                    if let Some(our_entry) = entry.our {
-                        let path = String::from_utf8_lossy(&our_entry.path).to_string();
-                        conflicted_files.push(path);
-                    } else if let Some(their_entry) = entry.their {
-                        let path = String::from_utf8_lossy(&their_entry.path).to_string();
-                        conflicted_files.push(path);
-                    }
-                }
-                
-                Ok(MergeResult {
-                    success: false,
-                    has_conflicts: true,
-                    message: format!("Merge from '{}' into '{}' has conflicts that need to be resolved", 
-                                    source_branch, head_name),
-                    conflicted_files,
-                })
+    if let Err(e) = repo.merge(&[&annotated_commit], None, Some(&mut checkout_options)) {
+        error!("Merge failed: {}", e);
+        
+        // Try to cleanup merge state
+        let _ = repo.cleanup_state();
+        
+        return Err(JanusError::GitError(format!("Failed to merge branches: {}", e)));
+    }
+    
+    let mut index = repo.index().map_err(|e| {
+        error!("Failed to get repository index: {}", e);
+        JanusError::GitError(format!("Failed to get repository index: {}", e))
+    })?;
+    
+    if mode == MergeMode::Squash {
+        // A squash merge records no MERGE_HEAD, only a prepared message for the next commit
+        repo.cleanup_state().map_err(|e| {
+            error!("Failed to cleanup merge state: {}", e);
+            JanusError::GitError(format!("Failed to cleanup merge state: {}", e))
+        })?;
+        let message = match &options.message {
+            Some(message) => message.clone(),
+            None => squash_message(&repo, head_commit.id(), source_commit.id())?,
+        };
+        fs::write(repo.path().join("SQUASH_MSG"), message)
+            .map_err(|e| JanusError::IoError(format!("Failed to write SQUASH_MSG: {}", e)))?;
+        
+        let conflicted_files = conflicted_paths(&index)?;
+        let has_conflicts = !conflicted_files.is_empty();
+        if !has_conflicts {
+            hooks::post_merge(&repo, true);
+        }
+        
+        return Ok(MergeResult {
+            success: !has_conflicts,
+            has_conflicts,
+            message: if has_conflicts {
+                format!("Squash merge of '{}' has conflicts that need to be resolved", source_branch)
             } else {
-                // No conflicts, create the merge commit
-                let signature = repo.signature().map_err(|e| {
-                    error!("Failed to get signature: {}", e);
-                    JanusError::GitError(format!("Failed to get signature: {}", e))
-                })?;
-                
-                let message = format!("Merge branch '{}' into '{}'", source_branch, head_name);
-                let head_commit = head.peel_to_commit().map_err(|e| {
-                    error!("Failed to get HEAD commit: {}", e);
-                    JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
-                })?;
-                
-                let tree_id = index.write_tree().map_err(|e| {
-                    error!("Failed to write merge tree: {}", e);
-                    JanusError::GitError(format!("Failed to write merge tree: {}", e))
-                })?;
-                
-                let tree = repo.find_tree(tree_id).map_err(|e| {
-                    error!("Failed to find tree: {}", e);
-                    JanusError::GitError(format!("Failed to find tree: {}", e))
-                })?;
-                
-                let _commit = repo.commit(
-                    Some("HEAD"),
-                    &signature,
-                    &signature,
-                    &message,
-                    &tree,
-                    &[&head_commit, &source_commit]
-                ).map_err(|e| {
-                    error!("Failed to create merge commit: {}", e);
-                    JanusError::GitError(format!("Failed to create merge commit: {}", e))
-                })?;
-                
-                // Clean up merge state
-                repo.cleanup_state().map_err(|e| {
-                    error!("Failed to cleanup merge state: {}", e);
-                    JanusError::GitError(format!("Failed to cleanup merge state: {}", e))
-                })?;
-                
-                Ok(MergeResult {
-                    success: true,
-                    has_conflicts: false,
-                    message: format!("Successfully merged '{}' into '{}'", source_branch, head_name),
-                    conflicted_files: Vec::new(),
-                })
-            }
-        },
-        Err(e) => {
-            error!("Merge failed: {}", e);
-            
-            // Try to cleanup merge state
-            let _ = repo.cleanup_state();
-            
-            Err(JanusError::GitError(format!("Failed to merge branches: {}", e)))
+                format!("Squashed '{}' into the index; commit to complete the merge", source_branch)
+            },
+            conflicted_files,
+            outcome: if has_conflicts { MergeOutcome::Conflicts } else { MergeOutcome::Squashed },
+            commit_id: Some(head_commit.id().to_string()),
+        });
+    }
+    
+    // Check for conflicts
+    if index.has_conflicts() {
+        info!("Merge has conflicts that need to be resolved");
+        
+        // Keep the requested message for when the merge is concluded
+        if let Some(message) = &options.message {
+            fs::write(repo.path().join("MERGE_MSG"), message)
+                .map_err(|e| JanusError::IoError(format!("Failed to write MERGE_MSG: {}", e)))?;
         }
+        
+        let conflicted_files = conflicted_paths(&index)?;
+        
+        return Ok(MergeResult {
+            success: false,
+            has_conflicts: true,
+            message: format!("Merge from '{}' into '{}' has conflicts that need to be resolved", 
+                            source_branch, head_name),
+            conflicted_files,
+            outcome: MergeOutcome::Conflicts,
+            commit_id: Some(head_commit.id().to_string()),
+        });
     }
+    
+    // No conflicts, create the merge commit
+    let signature = repo.signature().map_err(|e| {
+        error!("Failed to get signature: {}", e);
+        JanusError::GitError(format!("Failed to get signature: {}", e))
+    })?;
+    
+    let message = match &options.message {
+        Some(message) => git2::message_prettify(message, None)
+            .map_err(|e| JanusError::GitError(format!("Failed to clean up merge message: {}", e)))?,
+        None => format!("Merge branch '{}' into '{}'", source_branch, head_name),
+    };
+    
+    let tree_id = index.write_tree().map_err(|e| {
+        error!("Failed to write merge tree: {}", e);
+        JanusError::GitError(format!("Failed to write merge tree: {}", e))
+    })?;
+    
+    let tree = repo.find_tree(tree_id).map_err(|e| {
+        error!("Failed to find tree: {}", e);
+        JanusError::GitError(format!("Failed to find tree: {}", e))
+    })?;
+    
+    let commit_id = write_commit(&repo, &signature, &signature, &message, &tree, &[&head_commit, &source_commit])?;
+    
+    // Clean up merge state
+    repo.cleanup_state().map_err(|e| {
+        error!("Failed to cleanup merge state: {}", e);
+        JanusError::GitError(format!("Failed to cleanup merge state: {}", e))
+    })?;
+    
+    hooks::post_merge(&repo, false);
+    
+    Ok(MergeResult {
+        success: true,
+        has_conflicts: false,
+        message: format!("Successfully merged '{}' into '{}'", source_branch, head_name),
+        conflicted_files: Vec::new(),
+        outcome: MergeOutcome::MergeCommit,
+        commit_id: Some(commit_id.to_string()),
+    })
+}
+
+/// State of an in-progress merge
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeState {
+    pub in_progress: bool,
+    /// Commits being merged into HEAD, from `MERGE_HEAD`
+    pub merge_heads: Vec<String>,
+    /// Prepared message from `MERGE_MSG`
+    pub message: Option<String>,
+    pub conflicted_files: Vec<String>,
+    pub orig_head: Option<String>,
+}
+
+/// Reads the commits listed in `MERGE_HEAD`
+fn merge_heads(repo: &Repository) -> Result<Vec<Oid>, JanusError> {
+    let content = fs::read_to_string(repo.path().join("MERGE_HEAD")).map_err(|e| {
+        error!("Failed to read MERGE_HEAD: {}", e);
+        JanusError::IoError(format!("Failed to read MERGE_HEAD: {}", e))
+    })?;
+    
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Oid::from_str(line.trim()))
+        .collect::<Result<_, _>>()
+        .map_err(|e| JanusError::ParseError(format!("Invalid MERGE_HEAD: {}", e)))
+}
+
+/// Reads `MERGE_MSG`, dropping the comment lines git adds for the editor
+fn read_merge_message(repo: &Repository) -> Option<String> {
+    let content = fs::read_to_string(repo.path().join("MERGE_MSG")).ok()?;
+    let message: Vec<&str> = content.lines().filter(|line| !line.starts_with('#')).collect();
+    Some(message.join("\n").trim().to_string()).filter(|m| !m.is_empty())
+}
+
+fn ensure_merging(repo: &Repository) -> Result<(), JanusError> {
+    if repo.state() != git2::RepositoryState::Merge {
+        return Err(JanusError::GitError("No merge is in progress".to_string()));
+    }
+    Ok(())
+}
+
+/// Reports the merge in progress, if any
+#[tauri::command]
+pub fn get_merge_state(repo_path: String) -> Result<MergeState, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    
+    if repo.state() != git2::RepositoryState::Merge {
+        return Ok(MergeState {
+            in_progress: false,
+            merge_heads: Vec::new(),
+            message: None,
+            conflicted_files: Vec::new(),
+            orig_head: None,
+        });
+    }
+    
+    let orig_head = repo.revparse_single("ORIG_HEAD").ok().map(|obj| obj.id().to_string());
+    
+    Ok(MergeState {
+        in_progress: true,
+        merge_heads: merge_heads(&repo)?.iter().map(|oid| oid.to_string()).collect(),
+        message: read_merge_message(&repo),
+        conflicted_files: conflicted_paths(&repo.index()?)?,
+        orig_head,
+    })
+}
+
+/// Marks a conflicted file as resolved by staging its working tree version.
+/// A file deleted from the working tree is resolved as a deletion.
+#[tauri::command]
+pub fn mark_resolved(repo_path: String, file_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let mut index = repo.index().map_err(|e| {
+        error!("Failed to get repository index: {}", e);
+        JanusError::GitError(format!("Failed to get repository index: {}", e))
+    })?;
+    
+    if !conflicted_paths(&index)?.contains(&file_path) {
+        return Err(JanusError::ValidationError(format!("{} is not conflicted", file_path)));
+    }
+    
+    let workdir = repo.workdir()
+        .ok_or_else(|| JanusError::GitError("Cannot resolve conflicts in a bare repository".to_string()))?;
+    let result = if workdir.join(&file_path).exists() {
+        index.add_path(Path::new(&file_path))
+    } else {
+        index.remove_path(Path::new(&file_path))
+    };
+    result.and_then(|_| index.write()).map_err(|e| {
+        error!("Failed to mark {} as resolved: {}", file_path, e);
+        JanusError::GitError(format!("Failed to mark {} as resolved: {}", file_path, e))
+    })?;
+    
+    info!("Marked {} as resolved", file_path);
+    Ok(())
+}
+
+/// Concludes a merge whose conflicts have been resolved by committing the index
+#[tauri::command]
+pub fn merge_continue(repo_path: String, message: Option<String>) -> Result<MergeResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "merge_continue", "Conclude merge", ResetMode::Hard);
+    ensure_merging(&repo)?;
+    
+    let mut index = repo.index().map_err(|e| {
+        error!("Failed to get repository index: {}", e);
+        JanusError::GitError(format!("Failed to get repository index: {}", e))
+    })?;
+    
+    let conflicted_files = conflicted_paths(&index)?;
+    if !conflicted_files.is_empty() {
+        return Err(JanusError::GitError(format!(
+            "Resolve conflicts before continuing: {}", conflicted_files.join(", ")
+        )));
+    }
+    
+    let head_commit = repo.head().and_then(|h| h.peel_to_commit()).map_err(|e| {
+        error!("Failed to get HEAD commit: {}", e);
+        JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
+    })?;
+    let merged: Vec<Commit> = merge_heads(&repo)?
+        .into_iter()
+        .map(|oid| repo.find_commit(oid))
+        .collect::<Result<_, _>>()?;
+    
+    let message = message
+        .or_else(|| read_merge_message(&repo))
+        .unwrap_or_else(|| "Merge commit".to_string());
+    let message = git2::message_prettify(&message, None)
+        .map_err(|e| JanusError::GitError(format!("Failed to clean up merge message: {}", e)))?;
+    
+    let tree_id = index.write_tree().map_err(|e| {
+        error!("Failed to write merge tree: {}", e);
+        JanusError::GitError(format!("Failed to write merge tree: {}", e))
+    })?;
+    let tree = repo.find_tree(tree_id)?;
+    
+    let signature = repo.signature().map_err(|e| {
+        error!("Failed to get signature: {}", e);
+        JanusError::GitError(format!("Failed to get signature: {}", e))
+    })?;
+    
+    let mut parents = vec![&head_commit];
+    parents.extend(merged.iter());
+    let commit_id = write_commit(&repo, &signature, &signature, &message, &tree, &parents)?;
+    
+    repo.cleanup_state().map_err(|e| {
+        error!("Failed to cleanup merge state: {}", e);
+        JanusError::GitError(format!("Failed to cleanup merge state: {}", e))
+    })?;
+    
+    hooks::post_merge(&repo, false);
+    
+    info!("Concluded merge with commit {}", commit_id);
+    Ok(MergeResult {
+        success: true,
+        has_conflicts: false,
+        message: "Merge completed".to_string(),
+        conflicted_files: Vec::new(),
+        outcome: MergeOutcome::MergeCommit,
+        commit_id: Some(commit_id.to_string()),
+    })
+}
+
+/// Abandons a merge, restoring ORIG_HEAD and clearing the merge state
+#[tauri::command]
+pub fn merge_abort(repo_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    ensure_merging(&repo)?;
+    
+    // The conflicted files are about to be overwritten, so keep them for an undo
+    let mut journal = journal::Recorder::begin(&repo, "merge_abort", "Abort merge", ResetMode::Hard);
+    let snapshot = crate::snapshot::create_snapshot(&repo, "merge --abort")?;
+    journal.set_snapshot(snapshot);
+    
+    let target = repo.revparse_single("ORIG_HEAD")
+        .or_else(|_| repo.revparse_single("HEAD"))
+        .map_err(|e| {
+            error!("Failed to find ORIG_HEAD: {}", e);
+            JanusError::GitError(format!("Failed to find ORIG_HEAD: {}", e))
+        })?;
+    
+    // Like `git merge --abort`, only restore the paths the merge touched so
+    // unrelated uncommitted changes survive
+    let target_tree = target.peel_to_tree()?;
+    let index = repo.index()?;
+    let diff = repo.diff_tree_to_index(Some(&target_tree), Some(&index), None)?;
+    let mut touched: Vec<PathBuf> = diff.deltas()
+        .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()).map(Path::to_path_buf))
+        .collect();
+    touched.extend(conflicted_paths(&index)?.into_iter().map(PathBuf::from));
+    
+    if !touched.is_empty() {
+        let mut checkout_options = git2::build::CheckoutBuilder::new();
+        checkout_options.force();
+        for path in &touched {
+            checkout_options.path(path);
+        }
+        repo.checkout_tree(&target, Some(&mut checkout_options)).map_err(|e| {
+            error!("Failed to restore merged files: {}", e);
+            JanusError::GitError(format!("Failed to restore merged files: {}", e))
+        })?;
+    }
+    
+    // A mixed reset restores the index and removes MERGE_HEAD and MERGE_MSG
+    repo.reset(&target, git2::ResetType::Mixed, None).map_err(|e| {
+        error!("Failed to reset to {}: {}", target.id(), e);
+        JanusError::GitError(format!("Failed to reset to {}: {}", target.id(), e))
+    })?;
+    repo.cleanup_state()?;
+    
+    info!("Aborted merge, HEAD restored to {}", target.id());
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,239 @@
+use crate::error::JanusError;
+use crate::git::open_repo;
+use crate::paths;
+use git2::{Repository, StatusOptions, TreeWalkMode, TreeWalkResult};
+use log::error;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const DEFAULT_MAX_RESULTS: usize = 1000;
+// Larger files are skipped, like generated bundles and data dumps
+const DEFAULT_MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+// Bytes git looks at when deciding whether content is binary
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+/// Options for `grep_worktree`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct GrepOptions {
+    /// Search the files at this revision instead of the working tree
+    pub revspec: Option<String>,
+    /// Also search untracked files that are not ignored
+    pub include_untracked: bool,
+    pub ignore_case: bool,
+    /// Treat the pattern as a literal string instead of a regex
+    pub fixed_strings: bool,
+    /// Only search these files and directories
+    pub paths: Vec<String>,
+    /// Stop after this many matching lines
+    pub max_results: Option<usize>,
+    /// Skip files larger than this many bytes
+    pub max_file_size: Option<u64>,
+}
+
+/// Byte offsets of a match within its line
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrepLine {
+    /// One-based line number
+    pub line_number: usize,
+    pub line: String,
+    pub ranges: Vec<MatchRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrepFile {
+    pub path: String,
+    pub lines: Vec<GrepLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrepResult {
+    pub files: Vec<GrepFile>,
+    pub total_matches: usize,
+    /// True when the search stopped at `max_results`; which files made the cut is then arbitrary
+    pub truncated: bool,
+}
+
+/// A file to search: its path and where to read it from
+enum Source {
+    Disk,
+    Blob(git2::Oid),
+}
+
+fn in_filter(path: &str, filters: &[String]) -> bool {
+    filters.is_empty()
+        || filters
+            .iter()
+            .any(|filter| filter.is_empty() || path == filter || path.starts_with(&format!("{}/", filter)))
+}
+
+/// Tracked files, plus untracked ones that `.gitignore` does not exclude if asked for
+fn worktree_files(repo: &Repository, include_untracked: bool) -> Result<Vec<(String, Source)>, JanusError> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    let mut files: Vec<(String, Source)> = index
+        .iter()
+        // Symlinks could lead outside the worktree and gitlinks are other repositories
+        .filter(|entry| entry.mode != 0o120000 && entry.mode != 0o160000)
+        .map(|entry| (String::from_utf8_lossy(&entry.path).to_string(), Source::Disk))
+        .collect();
+    // A conflicted file has an entry per stage but only one copy on disk; entries are sorted by path
+    files.dedup_by(|a, b| a.0 == b.0);
+
+    if include_untracked {
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            if entry.status().is_wt_new() {
+                if let Some(path) = entry.path() {
+                    files.push((path.to_string(), Source::Disk));
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn revision_files(repo: &Repository, revspec: &str) -> Result<Vec<(String, Source)>, JanusError> {
+    let tree = repo.revparse_single(revspec).and_then(|obj| obj.peel_to_tree()).map_err(|e| {
+        error!("Failed to find {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to find {}: {}", revspec, e))
+    })?;
+
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) && entry.filemode() != 0o120000 {
+            let name = entry.name().unwrap_or("");
+            files.push((format!("{}{}", dir, name), Source::Blob(entry.id())));
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(files)
+}
+
+/// Matching lines are only kept while the shared budget lasts; a match refused for lack of
+/// budget sets `overflow`
+struct Budget {
+    left: AtomicUsize,
+    overflow: AtomicBool,
+}
+
+impl Budget {
+    /// Once a match has been refused, the remaining files need not be searched
+    fn overflowed(&self) -> bool {
+        self.overflow.load(Ordering::Relaxed)
+    }
+
+    fn claim(&self) -> bool {
+        let claimed = self.left.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| left.checked_sub(1)).is_ok();
+        if !claimed {
+            self.overflow.store(true, Ordering::Relaxed);
+        }
+        claimed
+    }
+}
+
+fn search_content(path: String, content: &[u8], pattern: &Regex, budget: &Budget) -> Option<GrepFile> {
+    if content[..content.len().min(BINARY_CHECK_LENGTH)].contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(content);
+    let mut lines = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let ranges: Vec<MatchRange> = pattern
+            .find_iter(line)
+            .map(|m| MatchRange { start: m.start(), end: m.end() })
+            .collect();
+        if ranges.is_empty() {
+            continue;
+        }
+        if !budget.claim() {
+            break;
+        }
+        lines.push(GrepLine { line_number: number + 1, line: line.to_string(), ranges });
+    }
+
+    (!lines.is_empty()).then_some(GrepFile { path, lines })
+}
+
+/// Searches files in parallel, sharing the result budget between workers
+pub fn grep(repo: &Repository, pattern: &str, options: &GrepOptions) -> Result<GrepResult, JanusError> {
+    let pattern = if options.fixed_strings { regex::escape(pattern) } else { pattern.to_string() };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case)
+        .build()
+        .map_err(|e| JanusError::ValidationError(format!("Invalid regular expression {}: {}", pattern, e)))?;
+
+    let filters: Vec<String> = options
+        .paths
+        .iter()
+        .map(|path| paths::validate_file_path(repo, path))
+        .collect::<Result<_, _>>()?;
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let max_file_size = options.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+
+    let files = match &options.revspec {
+        Some(revspec) => revision_files(repo, revspec)?,
+        None => worktree_files(repo, options.include_untracked)?,
+    };
+    let files: Vec<(String, Source)> = files.into_iter().filter(|(path, _)| in_filter(path, &filters)).collect();
+
+    let workdir = repo.workdir().map(Path::to_path_buf);
+    let git_dir = repo.path().to_path_buf();
+    let budget = Budget { left: AtomicUsize::new(max_results), overflow: AtomicBool::new(false) };
+
+    let mut found: Vec<GrepFile> = files
+        .into_par_iter()
+        .map_init(
+            || Repository::open(&git_dir).ok(),
+            |worker_repo, (path, source)| {
+                if budget.overflowed() {
+                    return None;
+                }
+                let content = match source {
+                    Source::Disk => {
+                        let full_path = workdir.as_ref()?.join(&path);
+                        let metadata = fs::symlink_metadata(&full_path).ok()?;
+                        if !metadata.is_file() || metadata.len() > max_file_size {
+                            return None;
+                        }
+                        fs::read(&full_path).ok()?
+                    }
+                    Source::Blob(oid) => {
+                        let blob = worker_repo.as_ref()?.find_blob(oid).ok()?;
+                        if blob.size() as u64 > max_file_size {
+                            return None;
+                        }
+                        blob.content().to_vec()
+                    }
+                };
+                search_content(path, &content, &regex, &budget)
+            },
+        )
+        .flatten()
+        .collect();
+
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    let total_matches = found.iter().map(|file| file.lines.len()).sum();
+    Ok(GrepResult { files: found, total_matches, truncated: budget.overflow.load(Ordering::Relaxed) })
+}
+
+/// Searches file contents like `git grep`, in the working tree or at a revision
+#[tauri::command(async)]
+pub fn grep_worktree(repo_path: String, pattern: String, options: Option<GrepOptions>) -> Result<GrepResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    grep(&repo, &pattern, &options.unwrap_or_default())
+}
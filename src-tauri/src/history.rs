@@ -0,0 +1,137 @@
+use crate::error::JanusError;
+use crate::git::{commit_to_info, open_repo, CommitInfo};
+use crate::paths;
+use git2::{Commit, DiffFindOptions, Repository, Sort, Tree, TreeEntry};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One commit in the history of a file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileHistoryEntry {
+    pub commit: CommitInfo,
+    /// Path of the file in this commit
+    pub path: String,
+    /// Path before a rename, when `change` is "renamed"
+    pub old_path: Option<String>,
+    /// "new", "modified", "deleted", "renamed" or "typechange", like `FileStatus`
+    pub change: String,
+}
+
+/// A page of file history, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileHistoryPage {
+    pub entries: Vec<FileHistoryEntry>,
+    pub has_more: bool,
+}
+
+fn entry_at(tree: Option<&Tree>, path: &str) -> Option<TreeEntry<'static>> {
+    tree.and_then(|tree| tree.get_path(Path::new(path)).ok())
+}
+
+fn same_entry(a: &TreeEntry, b: &TreeEntry) -> bool {
+    a.id() == b.id() && a.filemode() == b.filemode()
+}
+
+/// Finds the path `path` was renamed from between `parent` and `tree`
+fn renamed_from(repo: &Repository, parent: &Tree, tree: &Tree, path: &str) -> Result<Option<String>, JanusError> {
+    let mut diff = repo.diff_tree_to_tree(Some(parent), Some(tree), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    Ok(diff
+        .deltas()
+        .filter(|delta| delta.status() == git2::Delta::Renamed)
+        .find(|delta| delta.new_file().path() == Some(Path::new(path)))
+        .and_then(|delta| delta.old_file().path().map(|p| p.to_string_lossy().to_string())))
+}
+
+/// Works out how `commit` changed `path`, or None if it left the file alone.
+/// Merges only count when the file differs from every parent, like `git log`.
+fn change_in_commit(repo: &Repository, commit: &Commit, path: &str) -> Result<Option<(String, Option<String>)>, JanusError> {
+    let tree = commit.tree()?;
+    let parents: Vec<Tree> = commit.parents().map(|parent| parent.tree()).collect::<Result<_, _>>()?;
+
+    let current = entry_at(Some(&tree), path);
+    let unchanged_from = |parent: &Tree| match (&current, entry_at(Some(parent), path)) {
+        (Some(a), Some(b)) => same_entry(a, &b),
+        (None, None) => true,
+        _ => false,
+    };
+    if (parents.is_empty() && current.is_none()) || parents.iter().any(unchanged_from) {
+        return Ok(None);
+    }
+
+    let first_parent = parents.first();
+    let change = match (&current, entry_at(first_parent, path)) {
+        (Some(current), Some(previous)) if current.kind() != previous.kind() => ("typechange".to_string(), None),
+        (Some(_), Some(_)) => ("modified".to_string(), None),
+        (None, _) => ("deleted".to_string(), None),
+        (Some(_), None) => match first_parent {
+            Some(parent) => match renamed_from(repo, parent, &tree, path)? {
+                Some(old_path) => ("renamed".to_string(), Some(old_path)),
+                None => ("new".to_string(), None),
+            },
+            None => ("new".to_string(), None),
+        },
+    };
+    Ok(Some(change))
+}
+
+/// Lists the commits that changed a file, following it across renames
+#[tauri::command]
+pub fn get_file_history(
+    repo_path: String,
+    file_path: String,
+    revspec: Option<String>,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<FileHistoryPage, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let revspec = revspec.unwrap_or_else(|| "HEAD".to_string());
+    let skip = skip.unwrap_or(0) as usize;
+    let limit = limit.unwrap_or(100) as usize;
+
+    let start = repo.revparse_single(&revspec).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to find {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to find {}: {}", revspec, e))
+    })?;
+
+    let mut revwalk = repo.revwalk().map_err(|e| {
+        error!("Failed to create revision walker: {}", e);
+        JanusError::GitError(format!("Failed to create revision walker: {}", e))
+    })?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(start.id())?;
+
+    let mut path = file_path;
+    let mut matched = 0;
+    let mut entries = Vec::new();
+    let mut has_more = false;
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let Some((change, old_path)) = change_in_commit(&repo, &commit, &path)? else { continue };
+
+        matched += 1;
+        if matched > skip + limit {
+            has_more = true;
+            break;
+        }
+        if matched > skip {
+            entries.push(FileHistoryEntry {
+                commit: commit_to_info(&commit)?,
+                path: path.clone(),
+                old_path: old_path.clone(),
+                change,
+            });
+        }
+
+        // Older commits know the file by its previous name
+        if let Some(old_path) = old_path {
+            path = old_path;
+        }
+    }
+
+    Ok(FileHistoryPage { entries, has_more })
+}
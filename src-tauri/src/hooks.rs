@@ -0,0 +1,274 @@
+use crate::error::JanusError;
+use crate::worktree;
+use git2::Repository;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Maximum time a hook may run before it is killed
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Output captured from running a client-side hook
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookResult {
+    pub hook: String,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+}
+
+impl HookResult {
+    /// Returns true if the hook exited successfully
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+impl From<HookResult> for JanusError {
+    fn from(result: HookResult) -> Self {
+        let mut stderr = result.stderr;
+        if result.timed_out {
+            stderr.push_str(&format!("\nHook timed out after {} seconds", HOOK_TIMEOUT.as_secs()));
+        }
+
+        JanusError::HookFailed {
+            hook: result.hook,
+            exit_code: result.exit_code,
+            stdout: result.stdout,
+            stderr,
+        }
+    }
+}
+
+/// Get the directory hooks are loaded from, honouring `core.hooksPath`
+pub fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo.config().ok().and_then(|config| config.get_path("core.hooksPath").ok());
+
+    match configured {
+        // Relative hook paths are resolved against the working tree, like git does
+        Some(path) if path.is_relative() => work_dir(repo).join(path),
+        Some(path) => path,
+        // Linked worktrees share the hooks of the main repository
+        None => worktree::common_dir(repo).join("hooks"),
+    }
+}
+
+/// Directory hooks are executed in: the working tree, or the git dir for bare repositories
+fn work_dir(repo: &Repository) -> PathBuf {
+    repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Runs a hook with the default timeout.
+/// Returns None if the hook is not installed or not executable.
+pub fn run_hook(repo: &Repository, name: &str, args: &[&str], stdin: Option<&str>) -> Result<Option<HookResult>, JanusError> {
+    run_hook_with_timeout(repo, name, args, stdin, HOOK_TIMEOUT)
+}
+
+/// Runs a hook, killing it if it runs longer than `timeout`.
+/// Returns None if the hook is not installed or not executable.
+pub fn run_hook_with_timeout(
+    repo: &Repository,
+    name: &str,
+    args: &[&str],
+    stdin: Option<&str>,
+    timeout: Duration,
+) -> Result<Option<HookResult>, JanusError> {
+    let hook_path = hooks_dir(repo).join(name);
+    if !is_executable(&hook_path) {
+        return Ok(None);
+    }
+
+    info!("Running {} hook: {:?}", name, hook_path);
+    let mut child = Command::new(&hook_path)
+        .args(args)
+        .current_dir(work_dir(repo))
+        .env("GIT_DIR", repo.path())
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            error!("Failed to execute {} hook: {}", name, e);
+            JanusError::IoError(format!("Failed to execute {} hook: {}", name, e))
+        })?;
+
+    // Feed stdin and drain the pipes on threads so a chatty hook cannot block on a full pipe
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        let input = input.to_string();
+        thread::spawn(move || {
+            // The hook may exit without reading its input
+            let _ = pipe.write_all(input.as_bytes());
+        });
+    }
+    let stdout = spawn_reader(child.stdout.take());
+    let stderr = spawn_reader(child.stderr.take());
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                // Wait for the pipes to close so no trailing output is lost, but only for as long
+                // as the timeout allows, since a background grandchild may hold them open
+                let deadline = started + timeout;
+                join_reader(stdout.1, deadline);
+                join_reader(stderr.1, deadline);
+                break Some(status);
+            }
+            Ok(None) if started.elapsed() >= timeout => {
+                warn!("{} hook exceeded timeout of {:?}, killing it", name, timeout);
+                timed_out = true;
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => {
+                error!("Failed to wait for {} hook: {}", name, e);
+                return Err(JanusError::IoError(format!("Failed to wait for {} hook: {}", name, e)));
+            }
+        }
+    };
+
+    Ok(Some(HookResult {
+        hook: name.to_string(),
+        exit_code: status.and_then(|s| s.code()),
+        stdout: collected_output(&stdout.0),
+        stderr: collected_output(&stderr.0),
+        timed_out,
+    }))
+}
+
+type OutputBuffer = Arc<Mutex<Vec<u8>>>;
+
+/// Reads a pipe into a shared buffer as output arrives.
+/// Grandchildren of a hook may keep the pipe open after it exits or is killed,
+/// so the buffer is read without necessarily waiting for the reader to finish.
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> (OutputBuffer, thread::JoinHandle<()>) {
+    let buffer = OutputBuffer::default();
+    let shared = Arc::clone(&buffer);
+    let handle = thread::spawn(move || {
+        let Some(mut pipe) = pipe else { return };
+        let mut chunk = [0u8; 8192];
+        while let Ok(read) = pipe.read(&mut chunk) {
+            if read == 0 {
+                break;
+            }
+            if let Ok(mut output) = shared.lock() {
+                output.extend_from_slice(&chunk[..read]);
+            }
+        }
+    });
+    (buffer, handle)
+}
+
+/// Waits for a reader to reach the end of its pipe, leaving it behind at `deadline`
+fn join_reader(handle: thread::JoinHandle<()>, deadline: Instant) {
+    while !handle.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    if handle.is_finished() {
+        let _ = handle.join();
+    }
+}
+
+fn collected_output(buffer: &OutputBuffer) -> String {
+    buffer.lock().map(|output| String::from_utf8_lossy(&output).to_string()).unwrap_or_default()
+}
+
+/// Runs a hook that is allowed to abort the operation.
+/// A non-zero exit status is turned into `JanusError::HookFailed`.
+pub fn run_blocking_hook(repo: &Repository, name: &str, args: &[&str], stdin: Option<&str>) -> Result<Option<HookResult>, JanusError> {
+    match run_hook(repo, name, args, stdin)? {
+        Some(result) if !result.success() => {
+            error!("{} hook failed with exit code {:?}: {}", name, result.exit_code, result.stderr);
+            Err(result.into())
+        }
+        result => Ok(result),
+    }
+}
+
+/// Runs a hook whose exit status cannot affect the outcome, logging any failure
+pub fn run_notify_hook(repo: &Repository, name: &str, args: &[&str]) -> Option<HookResult> {
+    match run_hook(repo, name, args, None) {
+        Ok(Some(result)) => {
+            if !result.success() {
+                warn!("{} hook failed with exit code {:?}: {}", name, result.exit_code, result.stderr);
+            }
+            Some(result)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to run {} hook: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Runs `pre-commit`, which can abort a commit
+pub fn pre_commit(repo: &Repository) -> Result<Option<HookResult>, JanusError> {
+    run_blocking_hook(repo, "pre-commit", &[], None)
+}
+
+/// Runs `commit-msg` against the file holding the proposed message.
+/// The hook may edit the file in place.
+pub fn commit_msg(repo: &Repository, message_file: &Path) -> Result<Option<HookResult>, JanusError> {
+    let message_arg = message_file.to_string_lossy().to_string();
+    run_blocking_hook(repo, "commit-msg", &[&message_arg], None)
+}
+
+/// Runs `post-checkout` after HEAD moved from `old_head` to `new_head`
+pub fn post_checkout(repo: &Repository, old_head: git2::Oid, new_head: git2::Oid, branch_checkout: bool) -> Option<HookResult> {
+    let old_head = old_head.to_string();
+    let new_head = new_head.to_string();
+    let flag = if branch_checkout { "1" } else { "0" };
+    run_notify_hook(repo, "post-checkout", &[&old_head, &new_head, flag])
+}
+
+/// Runs `post-merge` after a successful merge
+pub fn post_merge(repo: &Repository, squash: bool) -> Option<HookResult> {
+    run_notify_hook(repo, "post-merge", &[if squash { "1" } else { "0" }])
+}
+
+/// Runs `pre-rebase`, which can abort a rebase of the current branch onto `upstream`
+pub fn pre_rebase(repo: &Repository, upstream: &str) -> Result<Option<HookResult>, JanusError> {
+    run_blocking_hook(repo, "pre-rebase", &[upstream], None)
+}
+
+/// A ref update about to be pushed, as passed to `pre-push` on stdin
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushUpdate {
+    pub local_ref: String,
+    pub local_oid: String,
+    pub remote_ref: String,
+    pub remote_oid: String,
+}
+
+/// Runs `pre-push`, which can abort a push
+pub fn pre_push(repo: &Repository, remote_name: &str, remote_url: &str, updates: &[PushUpdate]) -> Result<Option<HookResult>, JanusError> {
+    let stdin: String = updates
+        .iter()
+        .map(|u| format!("{} {} {} {}\n", u.local_ref, u.local_oid, u.remote_ref, u.remote_oid))
+        .collect();
+    run_blocking_hook(repo, "pre-push", &[remote_name, remote_url], Some(&stdin))
+}
@@ -0,0 +1,656 @@
+use crate::error::JanusError;
+use crate::git::{commit_to_info, conflicted_paths, open_repo, write_commit_object, CommitInfo, ResetMode};
+use crate::hooks;
+use crate::journal;
+use crate::sequencer::{clear_step_state, ensure_can_start, resolve_commit};
+use git2::{Commit, Oid, Repository, ResetType, Sort};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted when a `reword` step needs a new message from the user
+pub const REWORD_EVENT: &str = "rebase-reword";
+
+/// What an interactive rebase does with a commit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+/// One line of the todo list offered to the UI
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub commit: CommitInfo,
+}
+
+/// One step of the edited plan sent back by the UI
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RebasePlanStep {
+    pub action: RebaseAction,
+    pub commit_id: String,
+    /// New message for `reword`, or the combined message for `squash`.
+    /// A reword without a message prompts the user when it is reached.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Why an interactive rebase paused
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseStop {
+    Conflict,
+    Edit,
+    Reword,
+}
+
+/// Payload of `REWORD_EVENT`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RewordPrompt {
+    pub commit_id: String,
+    pub message: String,
+}
+
+/// Outcome of running the plan until it finishes or pauses
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InteractiveRebaseResult {
+    pub success: bool,
+    pub stopped: Option<RebaseStop>,
+    pub message: String,
+    pub conflicted_files: Vec<String>,
+    /// The step being applied when the rebase paused
+    pub current_commit: Option<String>,
+    pub steps_done: usize,
+    pub total_steps: usize,
+    pub head: String,
+}
+
+/// State of an in-progress interactive rebase
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InteractiveRebaseStatus {
+    pub in_progress: bool,
+    pub stopped: Option<RebaseStop>,
+    pub head_name: Option<String>,
+    pub onto: Option<String>,
+    pub current_commit: Option<String>,
+    pub remaining: Vec<RebasePlanStep>,
+    pub steps_done: usize,
+    pub total_steps: usize,
+    pub conflicted_files: Vec<String>,
+}
+
+/// Persistent plan state, stored as JSON in `.git/januslens-rebase`
+#[derive(Debug, Serialize, Deserialize)]
+struct PlanState {
+    head_name: Option<String>,
+    orig_head: String,
+    onto: String,
+    todo: Vec<RebasePlanStep>,
+    /// The step the rebase paused on, already removed from `todo`
+    current: Option<RebasePlanStep>,
+    stopped: Option<RebaseStop>,
+    done: usize,
+    total: usize,
+}
+
+impl PlanState {
+    fn dir(repo: &Repository) -> PathBuf {
+        repo.path().join("januslens-rebase")
+    }
+
+    fn load(repo: &Repository) -> Result<Self, JanusError> {
+        let path = Self::dir(repo).join("state.json");
+        if !path.exists() {
+            return Err(JanusError::GitError("No interactive rebase is in progress".to_string()));
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| JanusError::ParseError(format!("Failed to parse interactive rebase state: {}", e)))
+    }
+
+    fn save(&self, repo: &Repository) -> Result<(), JanusError> {
+        let dir = Self::dir(repo);
+        fs::create_dir_all(&dir)?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| JanusError::ParseError(format!("Failed to serialize interactive rebase state: {}", e)))?;
+        fs::write(dir.join("state.json"), content)?;
+        Ok(())
+    }
+
+    fn remove(repo: &Repository) -> Result<(), JanusError> {
+        let dir = Self::dir(repo);
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns true while an interactive rebase is paused in this repository
+pub(crate) fn in_progress(repo: &Repository) -> bool {
+    PlanState::dir(repo).join("state.json").exists()
+}
+
+/// Fails while a plan is paused. HEAD is detached and git itself sees no rebase, so commands
+/// that move HEAD or the index check this before running.
+pub(crate) fn ensure_not_in_progress(repo: &Repository) -> Result<(), JanusError> {
+    if in_progress(repo) {
+        return Err(JanusError::ValidationError(
+            "An interactive rebase is paused. Use interactive_rebase_continue or interactive_rebase_abort first.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn head_commit(repo: &Repository) -> Result<Commit<'_>, JanusError> {
+    repo.head().and_then(|head| head.peel_to_commit()).map_err(|e| {
+        error!("Failed to get HEAD commit: {}", e);
+        JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
+    })
+}
+
+/// Splits `fixup! subject` or `squash! subject` into the action and the target subject
+fn autosquash_target(summary: &str) -> Option<(RebaseAction, &str)> {
+    let (action, mut subject) = if let Some(rest) = summary.strip_prefix("fixup! ") {
+        (RebaseAction::Fixup, rest)
+    } else if let Some(rest) = summary.strip_prefix("squash! ") {
+        (RebaseAction::Squash, rest)
+    } else {
+        return None;
+    };
+
+    // "fixup! fixup! subject" still targets the original commit
+    while let Some(rest) = subject.strip_prefix("fixup! ").or_else(|| subject.strip_prefix("squash! ")) {
+        subject = rest;
+    }
+    Some((action, subject))
+}
+
+/// Moves `fixup!` and `squash!` commits after the commit they target, like `--autosquash`
+fn apply_autosquash(entries: Vec<RebaseTodoEntry>) -> Vec<RebaseTodoEntry> {
+    let mut result: Vec<RebaseTodoEntry> = Vec::with_capacity(entries.len());
+
+    for mut entry in entries {
+        if let Some((action, subject)) = autosquash_target(&entry.commit.summary) {
+            let target = result.iter().position(|e| {
+                e.commit.summary == subject || (subject.len() >= 4 && e.commit.id.starts_with(subject))
+            });
+
+            if let Some(target) = target {
+                // Keep earlier fixups for the same commit ahead of this one
+                let mut insert_at = target + 1;
+                while insert_at < result.len()
+                    && matches!(result[insert_at].action, RebaseAction::Fixup | RebaseAction::Squash)
+                {
+                    insert_at += 1;
+                }
+                entry.action = action;
+                result.insert(insert_at, entry);
+                continue;
+            }
+        }
+        result.push(entry);
+    }
+
+    result
+}
+
+/// Builds the todo list for `base..HEAD`, oldest commit first
+pub fn plan_rebase(repo: &Repository, base: &str, autosquash: bool) -> Result<Vec<RebaseTodoEntry>, JanusError> {
+    let base_commit = resolve_commit(repo, base)?;
+    let head = head_commit(repo)?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.hide(base_commit.id())?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    let mut entries = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            return Err(JanusError::ValidationError(format!(
+                "{}..HEAD contains merge commit {}, which cannot be rebased interactively",
+                base,
+                commit.id()
+            )));
+        }
+        entries.push(RebaseTodoEntry {
+            action: RebaseAction::Pick,
+            commit: commit_to_info(&commit)?,
+        });
+    }
+
+    Ok(if autosquash { apply_autosquash(entries) } else { entries })
+}
+
+/// Combines a squashed commit's message into the message it is folded into.
+/// A leading `squash!` subject only names the target, so it is dropped.
+fn squash_message(target: &str, squashed: &str) -> String {
+    let body = match squashed.split_once('\n') {
+        Some((subject, rest)) if subject.starts_with("squash! ") => rest,
+        None if squashed.starts_with("squash! ") => "",
+        _ => squashed,
+    };
+
+    if body.trim().is_empty() {
+        target.to_string()
+    } else {
+        format!("{}\n\n{}", target.trim_end(), body.trim())
+    }
+}
+
+fn prettify(message: &str) -> Result<String, JanusError> {
+    git2::message_prettify(message, None)
+        .map_err(|e| JanusError::GitError(format!("Failed to clean up commit message: {}", e)))
+}
+
+/// Replaces HEAD with a commit of the same parents and author
+fn amend_head(repo: &Repository, message: Option<&str>, tree_id: Option<Oid>) -> Result<Oid, JanusError> {
+    let head = head_commit(repo)?;
+    let tree = repo.find_tree(tree_id.unwrap_or_else(|| head.tree_id()))?;
+    let message = match message {
+        Some(message) => prettify(message)?,
+        None => head.message().unwrap_or("").to_string(),
+    };
+
+    let parents: Vec<Commit> = head.parents().collect();
+    let parent_refs: Vec<&Commit> = parents.iter().collect();
+    let oid = write_commit_object(repo, &head.author(), &repo.signature()?, &message, &tree, &parent_refs)?;
+    repo.set_head_detached(oid)?;
+    Ok(oid)
+}
+
+/// Commits the changes a step applied to the index
+fn commit_step(repo: &Repository, step: &RebasePlanStep, commit: &Commit) -> Result<(), JanusError> {
+    let head = head_commit(repo)?;
+    let tree_id = repo.index()?.write_tree()?;
+
+    match step.action {
+        RebaseAction::Squash | RebaseAction::Fixup => {
+            let message = match (&step.message, step.action) {
+                (Some(message), _) => message.clone(),
+                (None, RebaseAction::Squash) => {
+                    squash_message(head.message().unwrap_or(""), commit.message().unwrap_or(""))
+                }
+                (None, _) => head.message().unwrap_or("").to_string(),
+            };
+            amend_head(repo, Some(&message), Some(tree_id))?;
+        }
+        _ if tree_id == head.tree_id() => {
+            info!("Dropping {}: it no longer changes anything", commit.id());
+        }
+        _ => {
+            let message = match &step.message {
+                Some(message) if step.action == RebaseAction::Reword => prettify(message)?,
+                _ => commit.message().unwrap_or("").to_string(),
+            };
+            let tree = repo.find_tree(tree_id)?;
+            let oid = write_commit_object(repo, &commit.author(), &repo.signature()?, &message, &tree, &[&head])?;
+            repo.set_head_detached(oid)?;
+        }
+    }
+
+    clear_step_state(repo)
+}
+
+/// Applies one step, returning true if it left conflicts to resolve
+fn apply_step(repo: &Repository, step: &RebasePlanStep) -> Result<bool, JanusError> {
+    let oid = Oid::from_str(&step.commit_id)?;
+    let commit = repo.find_commit(oid)?;
+    let head = head_commit(repo)?;
+
+    // Reuse the commit as-is when it already sits on HEAD and keeps its message
+    let keeps_commit = match step.action {
+        RebaseAction::Pick | RebaseAction::Edit => true,
+        RebaseAction::Reword => step.message.is_none(),
+        _ => false,
+    };
+    if keeps_commit && commit.parent_id(0).ok() == Some(head.id()) {
+        repo.checkout_tree(commit.as_object(), None).map_err(|e| {
+            error!("Failed to checkout {}: {}", oid, e);
+            JanusError::GitError(format!("Failed to checkout {}: {}", oid, e))
+        })?;
+        repo.set_head_detached(oid)?;
+        return Ok(false);
+    }
+
+    repo.cherrypick(&commit, None).map_err(|e| {
+        error!("Failed to apply {}: {}", oid, e);
+        JanusError::GitError(format!("Failed to apply {}: {}", oid, e))
+    })?;
+
+    if repo.index()?.has_conflicts() {
+        return Ok(true);
+    }
+
+    commit_step(repo, step, &commit)?;
+    Ok(false)
+}
+
+fn stopped_result(repo: &Repository, state: &PlanState, message: String) -> Result<InteractiveRebaseResult, JanusError> {
+    Ok(InteractiveRebaseResult {
+        success: false,
+        stopped: state.stopped,
+        message,
+        conflicted_files: conflicted_paths(&repo.index()?)?,
+        current_commit: state.current.as_ref().map(|step| step.commit_id.clone()),
+        steps_done: state.done,
+        total_steps: state.total,
+        head: head_commit(repo)?.id().to_string(),
+    })
+}
+
+/// Pauses after a committed step if it is a `reword` awaiting a message or an `edit`
+fn pause_after(
+    repo: &Repository,
+    state: &mut PlanState,
+    step: RebasePlanStep,
+    on_reword: &dyn Fn(&RewordPrompt),
+) -> Result<Option<InteractiveRebaseResult>, JanusError> {
+    let stop = match step.action {
+        RebaseAction::Reword if step.message.is_none() => RebaseStop::Reword,
+        RebaseAction::Edit => RebaseStop::Edit,
+        _ => return Ok(None),
+    };
+
+    state.stopped = Some(stop);
+    state.current = Some(step);
+    state.save(repo)?;
+
+    let head = head_commit(repo)?;
+    let message = if stop == RebaseStop::Reword {
+        on_reword(&RewordPrompt {
+            commit_id: head.id().to_string(),
+            message: head.message().unwrap_or("").to_string(),
+        });
+        format!("Waiting for a new message for {}", head.id())
+    } else {
+        format!("Stopped to edit {}: amend it, then continue", head.id())
+    };
+
+    Ok(Some(stopped_result(repo, state, message)?))
+}
+
+/// Runs the remaining steps until the plan is finished or pauses
+fn run_plan(repo: &Repository, mut state: PlanState, on_reword: &dyn Fn(&RewordPrompt)) -> Result<InteractiveRebaseResult, JanusError> {
+    while !state.todo.is_empty() {
+        let step = state.todo.remove(0);
+        state.done += 1;
+
+        if step.action == RebaseAction::Drop {
+            continue;
+        }
+
+        if apply_step(repo, &step)? {
+            info!("Interactive rebase stopped at {} with conflicts", step.commit_id);
+            let message = format!("Could not apply {}: resolve the conflicts, then continue or abort", step.commit_id);
+            state.stopped = Some(RebaseStop::Conflict);
+            state.current = Some(step);
+            state.save(repo)?;
+            return stopped_result(repo, &state, message);
+        }
+
+        if let Some(result) = pause_after(repo, &mut state, step, on_reword)? {
+            return Ok(result);
+        }
+    }
+
+    finish_plan(repo, &state)
+}
+
+/// Moves the rebased branch to the new HEAD and re-attaches HEAD to it
+fn finish_plan(repo: &Repository, state: &PlanState) -> Result<InteractiveRebaseResult, JanusError> {
+    let head = head_commit(repo)?;
+
+    if let Some(branch) = &state.head_name {
+        repo.reference(branch, head.id(), true, &format!("rebase (finish): {} onto {}", branch, state.onto))
+            .map_err(|e| {
+                error!("Failed to update {}: {}", branch, e);
+                JanusError::GitError(format!("Failed to update {}: {}", branch, e))
+            })?;
+        repo.set_head(branch)?;
+    }
+
+    PlanState::remove(repo)?;
+
+    info!("Interactive rebase finished at {}", head.id());
+    Ok(InteractiveRebaseResult {
+        success: true,
+        stopped: None,
+        message: format!("Successfully rebased onto {}", state.onto),
+        conflicted_files: Vec::new(),
+        current_commit: None,
+        steps_done: state.done,
+        total_steps: state.total,
+        head: head.id().to_string(),
+    })
+}
+
+/// Starts executing an edited plan on top of `base`
+pub fn start_plan(
+    repo: &Repository,
+    base: &str,
+    plan: Vec<RebasePlanStep>,
+    on_reword: &dyn Fn(&RewordPrompt),
+) -> Result<InteractiveRebaseResult, JanusError> {
+    ensure_can_start(repo)?;
+
+    let onto = resolve_commit(repo, base)?;
+    let mut todo = Vec::with_capacity(plan.len());
+    let mut has_pick = false;
+    for mut step in plan {
+        let commit = resolve_commit(repo, &step.commit_id)?;
+        if commit.parent_count() > 1 {
+            return Err(JanusError::ValidationError(format!("Cannot rebase merge commit {}", commit.id())));
+        }
+        match step.action {
+            RebaseAction::Squash | RebaseAction::Fixup if !has_pick => {
+                return Err(JanusError::ValidationError(format!(
+                    "Cannot {:?} {} without a previous commit",
+                    step.action,
+                    commit.id()
+                )));
+            }
+            RebaseAction::Drop => {}
+            _ => has_pick = true,
+        }
+        step.commit_id = commit.id().to_string();
+        todo.push(step);
+    }
+
+    hooks::pre_rebase(repo, base)?;
+
+    let head = repo.head()?;
+    let state = PlanState {
+        head_name: if head.is_branch() { head.name().map(|name| name.to_string()) } else { None },
+        orig_head: head_commit(repo)?.id().to_string(),
+        onto: onto.id().to_string(),
+        total: todo.len(),
+        todo,
+        current: None,
+        stopped: None,
+        done: 0,
+    };
+
+    // Work on a detached HEAD so the branch only moves once the plan completes
+    repo.checkout_tree(onto.as_object(), None).map_err(|e| {
+        error!("Failed to checkout {}: {}", base, e);
+        JanusError::GitError(format!("Failed to checkout {}: {}", base, e))
+    })?;
+    repo.set_head_detached(onto.id())?;
+    state.save(repo)?;
+
+    info!("Started interactive rebase of {} steps onto {}", state.total, onto.id());
+    run_plan(repo, state, on_reword)
+}
+
+/// Resumes a paused plan.
+/// `message` rewords the current commit; at an `edit` stop, staged changes are amended into it.
+pub fn continue_plan(
+    repo: &Repository,
+    message: Option<String>,
+    on_reword: &dyn Fn(&RewordPrompt),
+) -> Result<InteractiveRebaseResult, JanusError> {
+    let mut state = PlanState::load(repo)?;
+    let stopped = state.stopped.take();
+    let current = state.current.take();
+
+    // Pick up whatever the user staged while the rebase was paused
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    match (stopped, current) {
+        (Some(RebaseStop::Conflict), Some(mut step)) => {
+            if index.has_conflicts() {
+                return Err(JanusError::GitError(format!(
+                    "Resolve conflicts before continuing: {}",
+                    conflicted_paths(&index)?.join(", ")
+                )));
+            }
+            if message.is_some() {
+                step.message = message;
+            }
+
+            let commit = repo.find_commit(Oid::from_str(&step.commit_id)?)?;
+            commit_step(repo, &step, &commit)?;
+            if let Some(result) = pause_after(repo, &mut state, step, on_reword)? {
+                return Ok(result);
+            }
+        }
+        (Some(RebaseStop::Reword), _) => {
+            if let Some(message) = message {
+                amend_head(repo, Some(&message), None)?;
+            }
+        }
+        (Some(RebaseStop::Edit), _) => {
+            let tree_id = index.write_tree()?;
+            if message.is_some() || tree_id != head_commit(repo)?.tree_id() {
+                amend_head(repo, message.as_deref(), Some(tree_id))?;
+            }
+        }
+        _ => {}
+    }
+
+    run_plan(repo, state, on_reword)
+}
+
+/// Abandons the plan and restores the branch to where it started
+pub fn abort_plan(repo: &Repository) -> Result<(), JanusError> {
+    let state = PlanState::load(repo)?;
+    let orig_head = repo.find_commit(Oid::from_str(&state.orig_head)?)?;
+
+    // The branch was never moved, so re-attaching HEAD to it restores the original commit
+    match &state.head_name {
+        Some(branch) => repo.set_head(branch)?,
+        None => repo.set_head_detached(orig_head.id())?,
+    }
+    repo.reset(orig_head.as_object(), ResetType::Hard, None).map_err(|e| {
+        error!("Failed to reset to {}: {}", orig_head.id(), e);
+        JanusError::GitError(format!("Failed to reset to {}: {}", orig_head.id(), e))
+    })?;
+
+    PlanState::remove(repo)?;
+    info!("Aborted interactive rebase, HEAD restored to {}", orig_head.id());
+    Ok(())
+}
+
+/// Forwards reword prompts to the frontend
+fn emit_reword(app: &AppHandle) -> impl Fn(&RewordPrompt) + '_ {
+    move |prompt| {
+        if let Err(e) = app.emit(REWORD_EVENT, prompt.clone()) {
+            warn!("Failed to emit {} event: {}", REWORD_EVENT, e);
+        }
+    }
+}
+
+/// Generates the editable todo list for `base..HEAD`.
+/// Autosquash defaults to the `rebase.autoSquash` setting.
+#[tauri::command]
+pub fn get_rebase_todo(repo_path: String, base: String, autosquash: Option<bool>) -> Result<Vec<RebaseTodoEntry>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let autosquash = autosquash.unwrap_or_else(|| {
+        repo.config()
+            .and_then(|config| config.get_bool("rebase.autoSquash"))
+            .unwrap_or(false)
+    });
+
+    plan_rebase(&repo, &base, autosquash)
+}
+
+/// Executes an edited todo list on top of `base`
+#[tauri::command]
+pub fn start_interactive_rebase(
+    app: AppHandle,
+    repo_path: String,
+    base: String,
+    plan: Vec<RebasePlanStep>,
+) -> Result<InteractiveRebaseResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "start_interactive_rebase", &format!("Interactive rebase onto {}", base), ResetMode::Hard);
+    start_plan(&repo, &base, plan, &emit_reword(&app))
+}
+
+/// Resumes a paused interactive rebase, optionally with a new message for the current commit
+#[tauri::command]
+pub fn interactive_rebase_continue(
+    app: AppHandle,
+    repo_path: String,
+    message: Option<String>,
+) -> Result<InteractiveRebaseResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "interactive_rebase_continue", "Continue interactive rebase", ResetMode::Hard);
+    continue_plan(&repo, message, &emit_reword(&app))
+}
+
+/// Abandons an interactive rebase
+#[tauri::command]
+pub fn interactive_rebase_abort(repo_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    abort_plan(&repo)
+}
+
+/// Reports a paused interactive rebase, including ones started before the app was restarted
+#[tauri::command]
+pub fn get_interactive_rebase_status(repo_path: String) -> Result<InteractiveRebaseStatus, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    plan_status(&repo)
+}
+
+pub(crate) fn plan_status(repo: &Repository) -> Result<InteractiveRebaseStatus, JanusError> {
+    if !in_progress(repo) {
+        return Ok(InteractiveRebaseStatus {
+            in_progress: false,
+            stopped: None,
+            head_name: None,
+            onto: None,
+            current_commit: None,
+            remaining: Vec::new(),
+            steps_done: 0,
+            total_steps: 0,
+            conflicted_files: Vec::new(),
+        });
+    }
+
+    let state = PlanState::load(repo)?;
+    Ok(InteractiveRebaseStatus {
+        in_progress: true,
+        stopped: state.stopped,
+        head_name: state.head_name.map(|name| name.trim_start_matches("refs/heads/").to_string()),
+        onto: Some(state.onto),
+        current_commit: state.current.map(|step| step.commit_id),
+        remaining: state.todo,
+        steps_done: state.done,
+        total_steps: state.total,
+        conflicted_files: conflicted_paths(&repo.index()?)?,
+    })
+}
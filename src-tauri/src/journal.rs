@@ -0,0 +1,374 @@
+use crate::error::JanusError;
+use crate::git::{get_config_dir, open_repo, ResetMode};
+use crate::repo_state::{self, RepoOperation};
+use crate::snapshot;
+use git2::build::CheckoutBuilder;
+use git2::{Oid, Repository};
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Entries kept per repository; older ones are dropped
+const MAX_JOURNAL_ENTRIES: usize = 50;
+const SYMBOLIC_PREFIX: &str = "ref: ";
+
+lazy_static! {
+    // Serialises reading and rewriting journal files within the app
+    static ref JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// How a reference changed. Values are commit ids, or "ref: <name>" for a symbolic
+/// reference such as HEAD on a branch; None means the reference did not exist.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RefChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A completed operation that can be undone
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalEntry {
+    pub id: String,
+    /// Name of the command, such as "merge_branch"
+    pub operation: String,
+    pub description: String,
+    pub time: i64,
+    pub refs: Vec<RefChange>,
+    /// Commit HEAD pointed to before and after the operation
+    pub head_before: Option<String>,
+    pub head_after: Option<String>,
+    /// How much of the index and working tree an undo puts back, as with `reset_to`
+    pub restore: ResetMode,
+    /// Uncommitted changes saved before the operation discarded them
+    pub snapshot: Option<String>,
+}
+
+/// Reference values captured at one point in time
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct RefState {
+    refs: BTreeMap<String, String>,
+    head: Option<String>,
+}
+
+/// The state from before an operation that stopped part way, such as a merge with conflicts.
+/// Once it finishes, the entry covers it from start to end.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PendingOperation {
+    operation: String,
+    description: String,
+    restore: ResetMode,
+    before: RefState,
+    snapshot: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct Journal {
+    repository: String,
+    entries: Vec<JournalEntry>,
+    pending: Option<PendingOperation>,
+}
+
+/// FNV-1a, so journal file names stay the same across builds
+fn path_hash(path: &Path) -> u64 {
+    path.to_string_lossy().bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn journal_path(repo: &Repository) -> Result<PathBuf, JanusError> {
+    Ok(get_config_dir()?.join("journal").join(format!("{:016x}.json", path_hash(repo.path()))))
+}
+
+/// Deletes the journal of a repository, so test runs do not leave journals behind
+#[cfg(test)]
+pub(crate) fn remove_journal(repo: &Repository) -> Result<(), JanusError> {
+    let _guard = lock_journal()?;
+    let path = journal_path(repo)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+impl Journal {
+    fn load(repo: &Repository) -> Result<Self, JanusError> {
+        let path = journal_path(repo)?;
+        if !path.exists() {
+            return Ok(Self { repository: repo.path().to_string_lossy().to_string(), ..Default::default() });
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| JanusError::IoError(format!("Failed to read undo journal {}: {}", path.display(), e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| JanusError::ConfigError(format!("Failed to parse undo journal {}: {}", path.display(), e)))
+    }
+
+    fn save(&self, repo: &Repository) -> Result<(), JanusError> {
+        let path = journal_path(repo)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| JanusError::IoError(format!("Failed to create journal directory: {}", e)))?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| JanusError::ConfigError(format!("Failed to serialize undo journal: {}", e)))?;
+        fs::write(&path, content)
+            .map_err(|e| JanusError::IoError(format!("Failed to write undo journal {}: {}", path.display(), e)))
+    }
+}
+
+fn lock_journal() -> Result<std::sync::MutexGuard<'static, ()>, JanusError> {
+    JOURNAL_LOCK.lock().map_err(|e| JanusError::UnknownError(format!("Failed to lock undo journal: {}", e)))
+}
+
+fn reference_value(reference: &git2::Reference) -> Option<String> {
+    match reference.symbolic_target() {
+        Some(target) => Some(format!("{}{}", SYMBOLIC_PREFIX, target)),
+        None => reference.target().map(|oid| oid.to_string()),
+    }
+}
+
+/// Records HEAD and the local branches, the refs the app's commands move
+fn capture(repo: &Repository) -> Result<RefState, JanusError> {
+    let mut refs = BTreeMap::new();
+    if let Some(value) = repo.find_reference("HEAD").ok().as_ref().and_then(reference_value) {
+        refs.insert("HEAD".to_string(), value);
+    }
+    for reference in repo.references_glob("refs/heads/*")? {
+        let reference = reference?;
+        if let (Some(name), Some(value)) = (reference.name(), reference_value(&reference)) {
+            refs.insert(name.to_string(), value);
+        }
+    }
+
+    let head = repo.head().and_then(|head| head.peel_to_commit()).ok().map(|commit| commit.id().to_string());
+    Ok(RefState { refs, head })
+}
+
+/// Whether an operation has stopped part way; a bisect leaves refs alone, so it does not count
+fn in_progress(repo: &Repository) -> bool {
+    repo_state::repository_state(repo)
+        .map(|state| !matches!(state.operation, RepoOperation::None | RepoOperation::Bisect { .. }))
+        .unwrap_or(false)
+}
+
+/// Records an operation in the undo journal when dropped, by comparing refs with how they
+/// were when it was created. Create one at the start of a command and keep it alive until
+/// the command returns.
+pub struct Recorder {
+    git_dir: PathBuf,
+    operation: String,
+    description: String,
+    restore: ResetMode,
+    before: Option<RefState>,
+    snapshot: Option<String>,
+}
+
+impl Recorder {
+    pub fn begin(repo: &Repository, operation: &str, description: &str, restore: ResetMode) -> Self {
+        let mut recorder = Self {
+            git_dir: repo.path().to_path_buf(),
+            operation: operation.to_string(),
+            description: description.to_string(),
+            restore,
+            before: None,
+            snapshot: None,
+        };
+
+        // Continuing a stopped operation picks up the state from before it started
+        let pending = lock_journal()
+            .and_then(|_guard| Journal::load(repo))
+            .ok()
+            .and_then(|journal| journal.pending)
+            .filter(|_| in_progress(repo));
+        match pending {
+            Some(pending) => {
+                recorder.operation = pending.operation;
+                recorder.description = pending.description;
+                recorder.restore = pending.restore;
+                recorder.snapshot = pending.snapshot;
+                recorder.before = Some(pending.before);
+            }
+            None => match capture(repo) {
+                Ok(before) => recorder.before = Some(before),
+                Err(e) => warn!("Failed to record refs before {}: {}", operation, e),
+            },
+        }
+        recorder
+    }
+
+    /// Notes a snapshot of uncommitted changes for the undo to restore
+    pub fn set_snapshot(&mut self, snapshot: Option<Oid>) {
+        if let Some(snapshot) = snapshot {
+            self.snapshot = Some(snapshot.to_string());
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), JanusError> {
+        let Some(before) = self.before.take() else { return Ok(()) };
+        let repo = Repository::open(&self.git_dir)?;
+        let _guard = lock_journal()?;
+        let mut journal = Journal::load(&repo)?;
+
+        if in_progress(&repo) {
+            journal.pending = Some(PendingOperation {
+                operation: self.operation.clone(),
+                description: self.description.clone(),
+                restore: self.restore,
+                before,
+                snapshot: self.snapshot.take(),
+            });
+            return journal.save(&repo);
+        }
+        let had_pending = journal.pending.take().is_some();
+
+        let after = capture(&repo)?;
+        let mut names: Vec<&String> = before.refs.keys().chain(after.refs.keys()).collect();
+        names.sort();
+        names.dedup();
+        let refs: Vec<RefChange> = names
+            .into_iter()
+            .map(|name| RefChange {
+                name: name.clone(),
+                before: before.refs.get(name).cloned(),
+                after: after.refs.get(name).cloned(),
+            })
+            .filter(|change| change.before != change.after)
+            .collect();
+
+        if refs.is_empty() && self.snapshot.is_none() {
+            return if had_pending { journal.save(&repo) } else { Ok(()) };
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        journal.entries.push(JournalEntry {
+            id: format!("{}-{}", now.as_millis(), journal.entries.len()),
+            operation: self.operation.clone(),
+            description: self.description.clone(),
+            time: now.as_secs() as i64,
+            refs,
+            head_before: before.head,
+            head_after: after.head,
+            restore: self.restore,
+            snapshot: self.snapshot.take(),
+        });
+        let excess = journal.entries.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        journal.entries.drain(..excess);
+        journal.save(&repo)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            warn!("Failed to record {} in the undo journal: {}", self.operation, e);
+        }
+    }
+}
+
+fn set_reference(repo: &Repository, name: &str, value: &str, log_message: &str) -> Result<(), JanusError> {
+    match value.strip_prefix(SYMBOLIC_PREFIX) {
+        Some(target) if name == "HEAD" => repo.set_head(target)?,
+        Some(target) => {
+            repo.reference_symbolic(name, target, true, log_message)?;
+        }
+        None if name == "HEAD" => repo.set_head_detached(Oid::from_str(value)?)?,
+        None => {
+            repo.reference(name, Oid::from_str(value)?, true, log_message)?;
+        }
+    }
+    Ok(())
+}
+
+/// Puts refs, and as much of the index and working tree as the entry asks for, back
+/// to how they were before the last recorded operation
+pub fn undo(repo: &Repository) -> Result<JournalEntry, JanusError> {
+    let _guard = lock_journal()?;
+    let mut journal = Journal::load(repo)?;
+    let entry = journal
+        .entries
+        .last()
+        .cloned()
+        .ok_or_else(|| JanusError::ValidationError("There is nothing to undo".to_string()))?;
+
+    if in_progress(repo) {
+        return Err(JanusError::ValidationError(
+            "Finish or abort the operation in progress before undoing".to_string(),
+        ));
+    }
+    let current = capture(repo)?;
+    for change in &entry.refs {
+        if current.refs.get(&change.name) != change.after.as_ref() {
+            return Err(JanusError::ValidationError(format!(
+                "Cannot undo \"{}\": {} has moved since",
+                entry.description, change.name
+            )));
+        }
+    }
+
+    let before_commit = entry
+        .head_before
+        .as_deref()
+        .map(|id| Oid::from_str(id).and_then(|oid| repo.find_commit(oid)))
+        .transpose()?;
+
+    // Safe checkout keeps uncommitted changes and fails rather than overwrite them
+    if let (ResetMode::Hard, Some(commit)) = (entry.restore, &before_commit) {
+        if current.head != entry.head_before {
+            repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe())).map_err(|e| {
+                error!("Failed to check out {} to undo {}: {}", commit.id(), entry.operation, e);
+                JanusError::GitError(format!("Cannot undo \"{}\" without losing local changes: {}", entry.description, e))
+            })?;
+        }
+    }
+
+    let log_message = format!("undo: {}", entry.description);
+    for change in entry.refs.iter().filter(|change| change.name != "HEAD") {
+        match &change.before {
+            Some(value) => set_reference(repo, &change.name, value, &log_message)?,
+            None => repo.find_reference(&change.name)?.delete()?,
+        }
+    }
+    if let Some(head) = entry.refs.iter().find(|change| change.name == "HEAD") {
+        if let Some(value) = &head.before {
+            set_reference(repo, "HEAD", value, &log_message)?;
+        }
+    }
+
+    if let (ResetMode::Mixed, Some(commit)) = (entry.restore, &before_commit) {
+        let mut index = repo.index()?;
+        index.read_tree(&commit.tree()?)?;
+        index.write()?;
+    }
+    if let Some(snapshot) = &entry.snapshot {
+        snapshot::apply_snapshot(repo, snapshot)?;
+    }
+
+    journal.entries.pop();
+    journal.save(repo)?;
+    info!("Undid {} ({})", entry.operation, entry.description);
+    Ok(entry)
+}
+
+/// Lists recorded operations, newest first
+#[tauri::command]
+pub fn get_undo_journal(repo_path: String) -> Result<Vec<JournalEntry>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _guard = lock_journal()?;
+    let mut entries = Journal::load(&repo)?.entries;
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Undoes the most recent recorded operation, refusing if its refs have moved since
+#[tauri::command]
+pub fn undo_last_operation(repo_path: String) -> Result<JournalEntry, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    undo(&repo)
+}
@@ -1,3 +1,23 @@
 pub mod git;
 pub mod error;
-pub mod logging; 
\ No newline at end of file
+pub mod logging;
+pub mod hooks;
+pub mod signing;
+pub mod sequencer;
+pub mod snapshot;
+pub mod rebase;
+pub mod interactive_rebase;
+pub mod conflicts;
+pub mod repo_state;
+pub mod blame;
+pub mod history;
+pub mod tree;
+pub mod paths;
+pub mod cancellation;
+pub mod search;
+pub mod grep;
+pub mod reflog;
+pub mod journal;
+pub mod worktree;
+pub mod submodule;
+pub mod clone;
\ No newline at end of file
@@ -6,6 +6,26 @@
 mod git;
 mod error;
 mod logging;
+mod hooks;
+mod signing;
+mod sequencer;
+mod snapshot;
+mod rebase;
+mod interactive_rebase;
+mod conflicts;
+mod repo_state;
+mod blame;
+mod history;
+mod tree;
+mod paths;
+mod cancellation;
+mod search;
+mod grep;
+mod reflog;
+mod journal;
+mod worktree;
+mod submodule;
+mod clone;
 #[cfg(test)]
 mod test_utils;
 #[cfg(test)]
@@ -14,6 +34,31 @@ mod tests {
     mod branch_operations_test;
     mod commit_history_test;
     mod file_diff_test;
+    mod commit_options_test;
+    mod hooks_test;
+    mod signing_test;
+    mod cherry_pick_test;
+    mod revert_test;
+    mod reset_test;
+    mod rebase_test;
+    mod interactive_rebase_test;
+    mod merge_modes_test;
+    mod merge_continue_test;
+    mod conflicts_test;
+    mod merge_preview_test;
+    mod repo_state_test;
+    mod blame_test;
+    mod file_history_test;
+    mod tree_test;
+    mod list_files_test;
+    mod path_validation_test;
+    mod search_commits_test;
+    mod grep_test;
+    mod reflog_test;
+    mod undo_journal_test;
+    mod worktree_test;
+    mod submodule_test;
+    mod clone_test;
 }
 
 use log::info;
@@ -38,6 +83,8 @@ fn main() {
             // Repository and branch commands
             git::list_repositories,
             git::open_repository,
+            git::init_repository,
+            clone::clone_repository,
             git::get_branches,
             git::get_commits,
             git::create_branch,
@@ -48,16 +95,87 @@ fn main() {
             // File operations
             git::list_files,
             git::get_status,
+            repo_state::get_repository_state,
             git::get_diff,
+
+            // History and blame
+            blame::get_blame,
+            history::get_file_history,
+            tree::list_tree,
+            tree::read_blob,
+            search::search_commits,
+            grep::grep_worktree,
+            cancellation::cancel_operation,
             
             // Staging and commit commands
             git::stage_file,
             git::unstage_file,
             git::create_commit,
+            git::get_known_authors,
+            git::get_commit_signature,
+            git::get_tag_signature,
 
             // Branch and merge operations
             git::checkout_branch,
             git::merge_branch,
+            git::preview_merge,
+            git::get_merge_state,
+            git::mark_resolved,
+            git::merge_continue,
+            git::merge_abort,
+            conflicts::get_conflict,
+            conflicts::resolve_conflict,
+            git::reset_to,
+
+            // Snapshots of uncommitted work
+            snapshot::list_snapshots,
+            snapshot::restore_snapshot,
+
+            // Reflog and lost commit recovery
+            reflog::get_reflog,
+            reflog::create_branch_at_reflog_entry,
+            reflog::reset_to_reflog_entry,
+            reflog::find_dangling_commits,
+
+            // Undo journal
+            journal::get_undo_journal,
+            journal::undo_last_operation,
+
+            // Worktrees
+            worktree::list_worktrees,
+            worktree::add_worktree,
+            worktree::lock_worktree,
+            worktree::unlock_worktree,
+            worktree::prune_worktrees,
+            worktree::remove_worktree,
+
+            // Submodules
+            submodule::list_submodules,
+            submodule::init_submodules,
+            submodule::update_submodules,
+            submodule::sync_submodules,
+            submodule::open_submodule,
+
+            // Cherry-pick and revert sequencer commands
+            sequencer::cherry_pick,
+            sequencer::cherry_pick_continue,
+            sequencer::cherry_pick_skip,
+            sequencer::cherry_pick_abort,
+            sequencer::revert_commit,
+            sequencer::revert_continue,
+            sequencer::revert_abort,
+
+            // Rebase commands
+            rebase::rebase,
+            rebase::rebase_continue,
+            rebase::rebase_skip,
+            rebase::rebase_abort,
+            rebase::get_rebase_status,
+            interactive_rebase::get_rebase_todo,
+            interactive_rebase::start_interactive_rebase,
+            interactive_rebase::interactive_rebase_continue,
+            interactive_rebase::interactive_rebase_abort,
+            interactive_rebase::get_interactive_rebase_status,
             
             // Logging commands
             logging::log_event_from_frontend,
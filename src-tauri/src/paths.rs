@@ -0,0 +1,80 @@
+use crate::error::JanusError;
+use git2::Repository;
+use std::path::{Component, Path, PathBuf};
+
+fn outside(path: &str) -> JanusError {
+    JanusError::ValidationError(format!("{} is outside the repository", path))
+}
+
+/// Rejects arguments that a command-line tool would read as an option
+pub fn validate_cli_arg(arg: &str) -> Result<(), JanusError> {
+    if arg.starts_with('-') {
+        return Err(JanusError::ValidationError(format!("{} looks like a command-line option", arg)));
+    }
+    Ok(())
+}
+
+/// Makes an absolute path relative to the worktree, allowing for a symlinked worktree location
+fn strip_workdir(workdir: &Path, path: &Path) -> Option<PathBuf> {
+    if let Ok(relative) = path.strip_prefix(workdir) {
+        return Some(relative.to_path_buf());
+    }
+    let workdir = workdir.canonicalize().ok()?;
+    path.strip_prefix(workdir).ok().map(Path::to_path_buf)
+}
+
+/// Resolves the path lexically, then follows symlinked directories on disk to make sure
+/// they stay inside the worktree. The last component is only followed when `is_dir` is set,
+/// so a tracked symlink can still be staged or diffed.
+fn validate(repo: &Repository, path: &str, is_dir: bool) -> Result<String, JanusError> {
+    validate_cli_arg(path)?;
+    if path.contains('\0') {
+        return Err(JanusError::ValidationError("Paths cannot contain NUL bytes".to_string()));
+    }
+
+    let workdir = repo.workdir();
+    let mut input = PathBuf::from(path);
+    if input.is_absolute() {
+        let workdir = workdir.ok_or_else(|| outside(path))?;
+        input = strip_workdir(workdir, &input).ok_or_else(|| outside(path))?;
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    for component in input.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop().ok_or_else(|| outside(path))?;
+            }
+            Component::RootDir | Component::Prefix(_) => return Err(outside(path)),
+        }
+    }
+
+    if let Some(workdir) = workdir {
+        let root = workdir.canonicalize()?;
+        let followed = if is_dir { parts.len() } else { parts.len().saturating_sub(1) };
+        let mut current = root.clone();
+        for part in &parts[..followed] {
+            match current.join(part).canonicalize() {
+                Ok(resolved) if resolved.starts_with(&root) => current = resolved,
+                Ok(_) => return Err(outside(path)),
+                // Nothing on disk can lead anywhere past a missing component
+                Err(_) => break,
+            }
+        }
+    }
+
+    Ok(parts.join("/"))
+}
+
+/// Checks a file path argument and returns it relative to the worktree root
+pub fn validate_file_path(repo: &Repository, path: &str) -> Result<String, JanusError> {
+    validate(repo, path, false)
+}
+
+/// Checks a directory path argument and returns it relative to the worktree root,
+/// or an empty string for the root itself
+pub fn validate_dir_path(repo: &Repository, path: &str) -> Result<String, JanusError> {
+    validate(repo, path, true)
+}
@@ -0,0 +1,254 @@
+use crate::error::JanusError;
+use crate::git::{conflicted_paths, open_repo, ResetMode};
+use crate::hooks;
+use crate::journal;
+use crate::sequencer::{ensure_can_start, resolve_commit};
+use git2::{build::CheckoutBuilder, ErrorCode, Oid, Rebase, Repository};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Outcome of running a rebase until it finishes or stops, reported like `MergeResult`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebaseResult {
+    pub success: bool,
+    pub has_conflicts: bool,
+    pub message: String,
+    pub conflicted_files: Vec<String>,
+    /// Commits created while running this part of the rebase
+    pub created_commits: Vec<String>,
+    /// The commit being replayed when the rebase stopped
+    pub current_commit: Option<String>,
+    /// One-based index of the step the rebase stopped at
+    pub current_step: Option<usize>,
+    pub total_steps: usize,
+}
+
+/// State of an in-progress rebase, read back from `.git/rebase-merge`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebaseStatus {
+    pub in_progress: bool,
+    /// Branch being rebased, or None for a detached HEAD
+    pub head_name: Option<String>,
+    pub orig_head: Option<String>,
+    pub onto: Option<String>,
+    pub current_commit: Option<String>,
+    pub current_step: Option<usize>,
+    pub total_steps: usize,
+    pub conflicted_files: Vec<String>,
+}
+
+fn open_rebase(repo: &Repository) -> Result<Rebase<'_>, JanusError> {
+    repo.open_rebase(None).map_err(|e| {
+        error!("Failed to open rebase state: {}", e);
+        if e.code() == ErrorCode::NotFound {
+            JanusError::GitError("No rebase is in progress".to_string())
+        } else {
+            JanusError::GitError(format!("Failed to open rebase state: {}", e))
+        }
+    })
+}
+
+fn current_commit(rebase: &mut Rebase) -> Option<Oid> {
+    let current = rebase.operation_current()?;
+    rebase.nth(current).map(|op| op.id())
+}
+
+/// Commits the current step, treating an already-applied patch as a no-op
+fn commit_current(repo: &Repository, rebase: &mut Rebase, created: &mut Vec<String>) -> Result<(), JanusError> {
+    let committer = repo.signature()?;
+    match rebase.commit(None, &committer, None) {
+        Ok(oid) => created.push(oid.to_string()),
+        Err(e) if e.code() == ErrorCode::Applied => {
+            info!("Skipping {:?}: its changes are already upstream", current_commit(rebase));
+        }
+        Err(e) => {
+            error!("Failed to commit rebased change: {}", e);
+            return Err(JanusError::GitError(format!("Failed to commit rebased change: {}", e)));
+        }
+    }
+    Ok(())
+}
+
+/// Replays the remaining operations, stopping at the first conflict
+fn run_rebase(repo: &Repository, mut rebase: Rebase, mut created: Vec<String>) -> Result<RebaseResult, JanusError> {
+    let total_steps = rebase.len();
+
+    while let Some(operation) = rebase.next() {
+        let operation = operation.map_err(|e| {
+            error!("Failed to apply rebase step: {}", e);
+            JanusError::GitError(format!("Failed to apply rebase step: {}", e))
+        })?;
+        let oid = operation.id();
+
+        let index = repo.index()?;
+        if index.has_conflicts() {
+            let conflicted_files = conflicted_paths(&index)?;
+            info!("Rebase stopped at {} with conflicts", oid);
+            return Ok(RebaseResult {
+                success: false,
+                has_conflicts: true,
+                message: format!("Could not apply {}: resolve the conflicts, then continue, skip or abort", oid),
+                conflicted_files,
+                created_commits: created,
+                current_commit: Some(oid.to_string()),
+                current_step: rebase.operation_current().map(|i| i + 1),
+                total_steps,
+            });
+        }
+
+        commit_current(repo, &mut rebase, &mut created)?;
+    }
+
+    rebase.finish(Some(&repo.signature()?)).map_err(|e| {
+        error!("Failed to finish rebase: {}", e);
+        JanusError::GitError(format!("Failed to finish rebase: {}", e))
+    })?;
+
+    info!("Rebase finished, {} commit(s) rewritten", created.len());
+    Ok(RebaseResult {
+        success: true,
+        has_conflicts: false,
+        message: format!("Rebased {} commit(s)", created.len()),
+        conflicted_files: Vec::new(),
+        created_commits: created,
+        current_commit: None,
+        current_step: None,
+        total_steps,
+    })
+}
+
+/// Rebases the current branch onto `upstream`, or onto `onto` when given
+#[tauri::command]
+pub fn rebase(repo_path: String, upstream: String, onto: Option<String>) -> Result<RebaseResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "rebase", &format!("Rebase onto {}", onto.as_deref().unwrap_or(&upstream)), ResetMode::Hard);
+    ensure_can_start(&repo)?;
+
+    let upstream_commit = resolve_commit(&repo, &upstream)?;
+    let onto_commit = onto.as_deref().map(|spec| resolve_commit(&repo, spec)).transpose()?;
+
+    hooks::pre_rebase(&repo, &upstream)?;
+
+    // Rebasing from the HEAD reference keeps the branch name, so finishing moves the branch
+    let head = repo.head()?;
+    let branch = repo.reference_to_annotated_commit(&head)?;
+    let upstream_annotated = repo.find_annotated_commit(upstream_commit.id())?;
+    let onto_annotated = onto_commit.map(|c| repo.find_annotated_commit(c.id())).transpose()?;
+
+    let rebase = repo
+        .rebase(Some(&branch), Some(&upstream_annotated), onto_annotated.as_ref(), None)
+        .map_err(|e| {
+            error!("Failed to start rebase onto {}: {}", upstream, e);
+            JanusError::GitError(format!("Failed to start rebase onto {}: {}", upstream, e))
+        })?;
+
+    info!("Started rebase of {} onto {}", head.shorthand().unwrap_or("HEAD"), onto.as_deref().unwrap_or(&upstream));
+    run_rebase(&repo, rebase, Vec::new())
+}
+
+/// Commits the resolved current step and carries on with the rebase
+#[tauri::command]
+pub fn rebase_continue(repo_path: String) -> Result<RebaseResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "rebase_continue", "Continue rebase", ResetMode::Hard);
+    let mut rebase = open_rebase(&repo)?;
+
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(JanusError::GitError(format!(
+            "Resolve conflicts before continuing: {}",
+            conflicted_paths(&index)?.join(", ")
+        )));
+    }
+
+    let mut created = Vec::new();
+    if rebase.operation_current().is_some() {
+        commit_current(&repo, &mut rebase, &mut created)?;
+    }
+
+    run_rebase(&repo, rebase, created)
+}
+
+/// Drops the current step's changes and carries on with the rebase
+#[tauri::command]
+pub fn rebase_skip(repo_path: String) -> Result<RebaseResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "rebase_skip", "Skip rebase step", ResetMode::Hard);
+    let rebase = open_rebase(&repo)?;
+
+    // A hard reset would also clear the rebase state, so restore HEAD's tree by hand
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read_tree(&head_tree)?;
+    index.write()?;
+    repo.checkout_head(Some(CheckoutBuilder::new().force())).map_err(|e| {
+        error!("Failed to discard skipped changes: {}", e);
+        JanusError::GitError(format!("Failed to discard skipped changes: {}", e))
+    })?;
+
+    run_rebase(&repo, rebase, Vec::new())
+}
+
+/// Abandons the rebase and restores the branch to where it started
+#[tauri::command]
+pub fn rebase_abort(repo_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let mut rebase = open_rebase(&repo)?;
+
+    rebase.abort().map_err(|e| {
+        error!("Failed to abort rebase: {}", e);
+        JanusError::GitError(format!("Failed to abort rebase: {}", e))
+    })?;
+
+    info!("Aborted rebase");
+    Ok(())
+}
+
+/// Reports any in-progress rebase, including ones started before the app was restarted
+#[tauri::command]
+pub fn get_rebase_status(repo_path: String) -> Result<RebaseStatus, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    rebase_status(&repo)
+}
+
+pub(crate) fn rebase_status(repo: &Repository) -> Result<RebaseStatus, JanusError> {
+    let mut rebase = match repo.open_rebase(None) {
+        Ok(rebase) => rebase,
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            return Ok(RebaseStatus {
+                in_progress: false,
+                head_name: None,
+                orig_head: None,
+                onto: None,
+                current_commit: None,
+                current_step: None,
+                total_steps: 0,
+                conflicted_files: Vec::new(),
+            });
+        }
+        Err(e) => {
+            error!("Failed to open rebase state: {}", e);
+            return Err(JanusError::GitError(format!("Failed to open rebase state: {}", e)));
+        }
+    };
+
+    // git2 does not expose the onto commit, so read it from the state directory
+    let onto = fs::read_to_string(repo.path().join("rebase-merge").join("onto"))
+        .ok()
+        .map(|s| s.trim().to_string());
+
+    Ok(RebaseStatus {
+        in_progress: true,
+        head_name: rebase
+            .orig_head_name()
+            .filter(|name| *name != "detached HEAD")
+            .map(|name| name.trim_start_matches("refs/heads/").to_string()),
+        orig_head: rebase.orig_head_id().map(|id| id.to_string()),
+        onto,
+        current_commit: current_commit(&mut rebase).map(|id| id.to_string()),
+        current_step: rebase.operation_current().map(|i| i + 1),
+        total_steps: rebase.len(),
+        conflicted_files: conflicted_paths(&repo.index()?)?,
+    })
+}
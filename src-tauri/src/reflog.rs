@@ -0,0 +1,160 @@
+use crate::error::JanusError;
+use crate::git::{self, commit_to_info, open_repo, BranchInfo, CommitInfo, ResetMode, ResetResult};
+use crate::journal;
+use git2::{ObjectType, Oid, Repository};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const DEFAULT_DANGLING_LIMIT: usize = 100;
+
+/// One movement of a reference, newest first like `git reflog`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReflogEntry {
+    /// Position in the reflog, as in `HEAD@{index}`
+    pub index: usize,
+    pub old_id: String,
+    pub new_id: String,
+    pub message: String,
+    pub committer: String,
+    pub committer_email: String,
+    pub time: i64,
+}
+
+/// Accepts HEAD, a full reference name or a local branch name
+fn reference_name(reference: Option<&str>) -> String {
+    match reference {
+        None | Some("HEAD") => "HEAD".to_string(),
+        Some(name) if name.starts_with("refs/") => name.to_string(),
+        Some(name) => format!("refs/heads/{}", name),
+    }
+}
+
+fn read_reflog(repo: &Repository, reference: Option<&str>) -> Result<Vec<ReflogEntry>, JanusError> {
+    let name = reference_name(reference);
+    repo.find_reference(&name).map_err(|e| {
+        error!("Failed to find reference {}: {}", name, e);
+        JanusError::GitError(format!("Failed to find reference {}: {}", name, e))
+    })?;
+
+    let reflog = repo.reflog(&name)?;
+    let entries = reflog
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let committer = entry.committer();
+            ReflogEntry {
+                index,
+                old_id: entry.id_old().to_string(),
+                new_id: entry.id_new().to_string(),
+                message: entry.message().unwrap_or("").to_string(),
+                committer: committer.name().unwrap_or("").to_string(),
+                committer_email: committer.email().unwrap_or("").to_string(),
+                time: committer.when().seconds(),
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// The commit a reflog entry moved its reference to
+fn entry_target(repo: &Repository, reference: Option<&str>, index: usize) -> Result<Oid, JanusError> {
+    let entries = read_reflog(repo, reference)?;
+    let entry = entries.get(index).ok_or_else(|| {
+        JanusError::ValidationError(format!("{} has no reflog entry {}", reference_name(reference), index))
+    })?;
+    let oid = Oid::from_str(&entry.new_id)?;
+    if oid.is_zero() {
+        return Err(JanusError::ValidationError(format!("Reflog entry {} deleted its reference", index)));
+    }
+    Ok(oid)
+}
+
+/// Lists the reflog of HEAD, or of a branch or other reference
+#[tauri::command]
+pub fn get_reflog(repo_path: String, reference: Option<String>) -> Result<Vec<ReflogEntry>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    read_reflog(&repo, reference.as_deref())
+}
+
+/// Creates a branch at the commit a reflog entry points to
+#[tauri::command]
+pub fn create_branch_at_reflog_entry(
+    repo_path: String,
+    reference: Option<String>,
+    index: usize,
+    branch_name: String,
+) -> Result<BranchInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let oid = entry_target(&repo, reference.as_deref(), index)?;
+    let _journal = journal::Recorder::begin(&repo, "create_branch", &format!("Create branch {}", branch_name), ResetMode::Soft);
+    let commit = repo.find_commit(oid).map_err(|e| {
+        error!("Failed to find commit {}: {}", oid, e);
+        JanusError::GitError(format!("Commit {} is no longer available: {}", oid, e))
+    })?;
+
+    repo.branch(&branch_name, &commit, false).map_err(|e| {
+        error!("Failed to create branch {}: {}", branch_name, e);
+        JanusError::GitError(format!("Failed to create branch {}: {}", branch_name, e))
+    })?;
+
+    info!("Created branch {} at {} from the reflog", branch_name, oid);
+    Ok(BranchInfo { name: branch_name, is_head: false, upstream: None, commit_id: oid.to_string() })
+}
+
+/// Resets the current branch to the commit a reflog entry points to
+#[tauri::command]
+pub fn reset_to_reflog_entry(
+    repo_path: String,
+    reference: Option<String>,
+    index: usize,
+    mode: ResetMode,
+) -> Result<ResetResult, JanusError> {
+    let oid = {
+        let repo = open_repo(&repo_path)?;
+        entry_target(&repo, reference.as_deref(), index)?
+    };
+    git::reset_to(repo_path, oid.to_string(), mode)
+}
+
+/// Finds commits that no reference reaches, like `git fsck --lost-found`.
+/// Only the tips are returned; their ancestors are reachable from them.
+pub fn dangling_commits(repo: &Repository, limit: usize) -> Result<Vec<CommitInfo>, JanusError> {
+    let odb = repo.odb()?;
+    let mut commits = Vec::new();
+    odb.foreach(|oid| {
+        if let Ok((_, ObjectType::Commit)) = odb.read_header(*oid) {
+            commits.push(*oid);
+        }
+        true
+    })?;
+
+    let mut revwalk = repo.revwalk()?;
+    for reference in repo.references()?.flatten() {
+        if let Ok(commit) = reference.peel_to_commit() {
+            revwalk.push(commit.id())?;
+        }
+    }
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+        revwalk.push(head.id())?;
+    }
+    let reachable: HashSet<Oid> = revwalk.collect::<Result<_, _>>()?;
+
+    let unreachable: Vec<git2::Commit> = commits
+        .into_iter()
+        .filter(|oid| !reachable.contains(oid))
+        .filter_map(|oid| repo.find_commit(oid).ok())
+        .collect();
+    let parents: HashSet<Oid> = unreachable.iter().flat_map(|commit| commit.parent_ids()).collect();
+
+    let mut tips: Vec<&git2::Commit> = unreachable.iter().filter(|commit| !parents.contains(&commit.id())).collect();
+    tips.sort_by_key(|commit| std::cmp::Reverse(commit.time().seconds()));
+    tips.into_iter().take(limit).map(commit_to_info).collect()
+}
+
+/// Lists lost commits, newest first, so they can be inspected and recovered
+#[tauri::command]
+pub fn find_dangling_commits(repo_path: String, limit: Option<usize>) -> Result<Vec<CommitInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    dangling_commits(&repo, limit.unwrap_or(DEFAULT_DANGLING_LIMIT))
+}
@@ -0,0 +1,185 @@
+use crate::error::JanusError;
+use crate::git::{conflicted_paths, open_repo};
+use crate::interactive_rebase;
+use crate::rebase;
+use crate::sequencer::{self, SequencerAction};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// The multi-step operation a repository is in the middle of
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RepoOperation {
+    None,
+    Merge {
+        merge_heads: Vec<String>,
+    },
+    Rebase {
+        /// True for interactive rebases, including ones planned in the app
+        interactive: bool,
+        /// Branch being rebased, or None for a detached HEAD
+        head_name: Option<String>,
+        onto: Option<String>,
+        current_commit: Option<String>,
+        /// One-based index of the current step
+        current_step: Option<usize>,
+        total_steps: usize,
+    },
+    CherryPick {
+        current_commit: Option<String>,
+        /// Number of commits still to apply, including the current one
+        remaining: usize,
+    },
+    Revert {
+        current_commit: Option<String>,
+        remaining: usize,
+    },
+    Bisect {
+        /// Branch or commit checked out when the bisect started
+        start: Option<String>,
+        bad: Option<String>,
+        good: Vec<String>,
+    },
+    ApplyMailbox {
+        current_step: Option<usize>,
+        total_steps: usize,
+    },
+}
+
+/// What the repository is doing and what stands in the way of continuing it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepoState {
+    pub operation: RepoOperation,
+    pub conflicted_files: Vec<String>,
+}
+
+fn read_state_file(repo: &Repository, name: &str) -> Option<String> {
+    fs::read_to_string(repo.path().join(name))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Reads a commit id such as CHERRY_PICK_HEAD from the git directory
+fn read_head_file(repo: &Repository, name: &str) -> Option<String> {
+    read_state_file(repo, name).filter(|content| Oid::from_str(content).is_ok())
+}
+
+fn merge_heads(repo: &Repository) -> Vec<String> {
+    read_state_file(repo, "MERGE_HEAD")
+        .map(|content| content.lines().map(|line| line.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn bisect_state(repo: &Repository) -> Result<RepoOperation, JanusError> {
+    let mut bad = None;
+    let mut good = Vec::new();
+    for reference in repo.references_glob("refs/bisect/*")? {
+        let reference = reference?;
+        let (Some(name), Some(target)) = (reference.name(), reference.target()) else { continue };
+        if name == "refs/bisect/bad" {
+            bad = Some(target.to_string());
+        } else if name.starts_with("refs/bisect/good-") {
+            good.push(target.to_string());
+        }
+    }
+
+    Ok(RepoOperation::Bisect { start: read_state_file(repo, "BISECT_START"), bad, good })
+}
+
+/// Step counters kept by `git am` and the apply backend of `git rebase` in `.git/rebase-apply`
+fn apply_progress(repo: &Repository) -> (Option<usize>, usize) {
+    let number = |name: &str| read_state_file(repo, &format!("rebase-apply/{}", name)).and_then(|n| n.parse().ok());
+    (number("next"), number("last").unwrap_or(0))
+}
+
+/// Works out the in-progress operation from git's state files and the app's own sequencer state
+pub fn repository_state(repo: &Repository) -> Result<RepoState, JanusError> {
+    let operation = if interactive_rebase::in_progress(repo) {
+        let status = interactive_rebase::plan_status(repo)?;
+        RepoOperation::Rebase {
+            interactive: true,
+            head_name: status.head_name,
+            onto: status.onto,
+            current_commit: status.current_commit,
+            current_step: Some(status.steps_done).filter(|step| *step > 0),
+            total_steps: status.total_steps,
+        }
+    } else {
+        match repo.state() {
+            git2::RepositoryState::Rebase
+            | git2::RepositoryState::RebaseInteractive
+            | git2::RepositoryState::RebaseMerge => {
+                let status = rebase::rebase_status(repo)?;
+                RepoOperation::Rebase {
+                    interactive: repo.state() == git2::RepositoryState::RebaseInteractive,
+                    head_name: status.head_name,
+                    onto: status.onto,
+                    current_commit: status.current_commit,
+                    current_step: status.current_step,
+                    total_steps: status.total_steps,
+                }
+            }
+            git2::RepositoryState::ApplyMailboxOrRebase if repo.path().join("rebase-apply/rebasing").exists() => {
+                let (current_step, total_steps) = apply_progress(repo);
+                RepoOperation::Rebase {
+                    interactive: false,
+                    head_name: read_state_file(repo, "rebase-apply/head-name")
+                        .filter(|name| name != "detached HEAD")
+                        .map(|name| name.trim_start_matches("refs/heads/").to_string()),
+                    onto: read_state_file(repo, "rebase-apply/onto"),
+                    current_commit: read_state_file(repo, "rebase-apply/original-commit"),
+                    current_step,
+                    total_steps,
+                }
+            }
+            git2::RepositoryState::ApplyMailbox | git2::RepositoryState::ApplyMailboxOrRebase => {
+                let (current_step, total_steps) = apply_progress(repo);
+                RepoOperation::ApplyMailbox { current_step, total_steps }
+            }
+            git2::RepositoryState::Merge => RepoOperation::Merge { merge_heads: merge_heads(repo) },
+            git2::RepositoryState::Bisect => bisect_state(repo)?,
+            state => match sequencer::sequence_progress(repo)? {
+                // The app's own sequence keeps its todo list between steps
+                Some(progress) => {
+                    let current_commit = Some(progress.current_commit.to_string());
+                    match progress.action {
+                        SequencerAction::Pick => RepoOperation::CherryPick { current_commit, remaining: progress.remaining },
+                        SequencerAction::Revert => RepoOperation::Revert { current_commit, remaining: progress.remaining },
+                    }
+                }
+                // A single pick or revert started outside the app
+                None => match state {
+                    git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence => RepoOperation::CherryPick {
+                        current_commit: read_head_file(repo, "CHERRY_PICK_HEAD"),
+                        remaining: 1,
+                    },
+                    git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence => RepoOperation::Revert {
+                        current_commit: read_head_file(repo, "REVERT_HEAD"),
+                        remaining: 1,
+                    },
+                    _ => RepoOperation::None,
+                },
+            },
+        }
+    };
+
+    let conflicted_files = match operation {
+        RepoOperation::None | RepoOperation::Bisect { .. } => Vec::new(),
+        _ => {
+            let mut index = repo.index()?;
+            index.read(false)?;
+            conflicted_paths(&index)?
+        }
+    };
+
+    Ok(RepoState { operation, conflicted_files })
+}
+
+/// Reports which merge, rebase, cherry-pick, revert or bisect is in progress
+#[tauri::command]
+pub fn get_repository_state(repo_path: String) -> Result<RepoState, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    repository_state(&repo)
+}
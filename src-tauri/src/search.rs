@@ -0,0 +1,293 @@
+use crate::cancellation::CancelToken;
+use crate::error::JanusError;
+use crate::git::{commit_to_info, open_repo, CommitInfo};
+use crate::paths;
+use git2::{Commit, DiffOptions, Oid, Repository, RevparseMode, Sort};
+use log::{error, warn};
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted after each batch of commits has been searched
+pub const SEARCH_PROGRESS_EVENT: &str = "search-commits-progress";
+
+// Commits handed to rayon at a time, and so the granularity of progress and cancellation
+const SEARCH_BATCH_SIZE: usize = 256;
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+/// Criteria for `search_commits`; every criterion given must match
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CommitSearchQuery {
+    /// Regex matched against the full commit message
+    pub message: Option<String>,
+    /// Regex matched against "Name <email>" of the author
+    pub author: Option<String>,
+    /// Regex matched against "Name <email>" of the committer
+    pub committer: Option<String>,
+    /// Commits that change how often this string occurs, like `git log -S`
+    pub pickaxe: Option<String>,
+    /// Commits whose added or removed lines match this regex, like `git log -G`
+    pub diff_regex: Option<String>,
+    /// Only commits that touch one of these paths
+    pub paths: Vec<String>,
+    /// A revision such as `main`, or a range such as `v1.0..main`. Defaults to HEAD.
+    pub range: Option<String>,
+    /// Match the patterns and the pickaxe string without regard to case, like `git log -i`
+    pub ignore_case: bool,
+    pub limit: Option<usize>,
+}
+
+/// Matches found in one batch, sent with `SEARCH_PROGRESS_EVENT`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitSearchProgress {
+    pub search_id: String,
+    pub matches: Vec<CommitInfo>,
+    pub scanned: usize,
+    pub total: usize,
+}
+
+/// Final outcome of a commit search
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitSearchResult {
+    pub matches: Vec<CommitInfo>,
+    pub scanned: usize,
+    pub total: usize,
+    pub cancelled: bool,
+    /// True when the search stopped at the result limit
+    pub truncated: bool,
+}
+
+/// The query with its regexes compiled and paths validated
+struct Matcher {
+    message: Option<Regex>,
+    author: Option<Regex>,
+    committer: Option<Regex>,
+    /// The pickaxe string, escaped so it matches literally
+    pickaxe: Option<Regex>,
+    diff_regex: Option<Regex>,
+    paths: Vec<String>,
+}
+
+fn compile(pattern: &Option<String>, ignore_case: bool) -> Result<Option<Regex>, JanusError> {
+    pattern
+        .as_deref()
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()
+                .map_err(|e| JanusError::ValidationError(format!("Invalid regular expression {}: {}", pattern, e)))
+        })
+        .transpose()
+}
+
+impl Matcher {
+    fn new(repo: &Repository, query: &CommitSearchQuery) -> Result<Self, JanusError> {
+        Ok(Self {
+            message: compile(&query.message, query.ignore_case)?,
+            author: compile(&query.author, query.ignore_case)?,
+            committer: compile(&query.committer, query.ignore_case)?,
+            pickaxe: compile(&query.pickaxe.as_deref().filter(|s| !s.is_empty()).map(regex::escape), query.ignore_case)?,
+            diff_regex: compile(&query.diff_regex, query.ignore_case)?,
+            paths: query.paths.iter().map(|path| paths::validate_file_path(repo, path)).collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn needs_diff(&self) -> bool {
+        !self.paths.is_empty() || self.pickaxe.is_some() || self.diff_regex.is_some()
+    }
+
+    fn matches(&self, repo: &Repository, commit: &Commit) -> Result<bool, JanusError> {
+        let identity = |signature: git2::Signature| {
+            format!("{} <{}>", signature.name().unwrap_or(""), signature.email().unwrap_or(""))
+        };
+        if let Some(message) = &self.message {
+            if !message.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+        if let Some(author) = &self.author {
+            if !author.is_match(&identity(commit.author())) {
+                return Ok(false);
+            }
+        }
+        if let Some(committer) = &self.committer {
+            if !committer.is_match(&identity(commit.committer())) {
+                return Ok(false);
+            }
+        }
+        if !self.needs_diff() {
+            return Ok(true);
+        }
+
+        // Compared with the first parent, or with nothing for a root commit
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let mut options = DiffOptions::new();
+        for path in &self.paths {
+            options.pathspec(path);
+        }
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut options))?;
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+
+        if let Some(needle) = &self.pickaxe {
+            let occurrences = |oid: Oid| -> usize {
+                match repo.find_blob(oid) {
+                    Ok(blob) if !blob.is_binary() => {
+                        needle.find_iter(&String::from_utf8_lossy(blob.content())).count()
+                    }
+                    _ => 0,
+                }
+            };
+            let changed = diff
+                .deltas()
+                .any(|delta| occurrences(delta.old_file().id()) != occurrences(delta.new_file().id()));
+            if !changed {
+                return Ok(false);
+            }
+        }
+
+        if let Some(pattern) = &self.diff_regex {
+            let mut found = false;
+            // Returning false from the callback stops the diff early once a line matches
+            let result = diff.foreach(
+                &mut |_, _| true,
+                None,
+                None,
+                Some(&mut |_, _, line| {
+                    if matches!(line.origin(), '+' | '-') && pattern.is_match(&String::from_utf8_lossy(line.content())) {
+                        found = true;
+                    }
+                    !found
+                }),
+            );
+            if let Err(e) = result {
+                if !found {
+                    return Err(e.into());
+                }
+            }
+            if !found {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Lists the commits to search, newest first
+fn walk_range(repo: &Repository, range: Option<&str>) -> Result<Vec<Oid>, JanusError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let range = range.unwrap_or("HEAD");
+    let spec = repo.revparse(range).map_err(|e| {
+        error!("Failed to parse {}: {}", range, e);
+        JanusError::GitError(format!("Failed to parse {}: {}", range, e))
+    })?;
+    let to = spec.to().map(|obj| obj.peel_to_commit()).transpose()?;
+    let from = spec.from().map(|obj| obj.peel_to_commit()).transpose()?;
+
+    match (from, to) {
+        (Some(from), Some(to)) if spec.mode().contains(RevparseMode::MERGE_BASE) => {
+            revwalk.push(from.id())?;
+            revwalk.push(to.id())?;
+            revwalk.hide(repo.merge_base(from.id(), to.id())?)?;
+        }
+        (Some(from), Some(to)) => {
+            revwalk.push(to.id())?;
+            revwalk.hide(from.id())?;
+        }
+        (Some(single), None) | (None, Some(single)) => revwalk.push(single.id())?,
+        (None, None) => return Err(JanusError::ValidationError(format!("{} does not name any commits", range))),
+    }
+
+    Ok(revwalk.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Searches the history in parallel batches, reporting each batch's matches as it goes
+pub fn search(
+    repo: &Repository,
+    search_id: &str,
+    query: &CommitSearchQuery,
+    cancel: &AtomicBool,
+    on_progress: &dyn Fn(&CommitSearchProgress),
+) -> Result<CommitSearchResult, JanusError> {
+    let matcher = Matcher::new(repo, query)?;
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let oids = walk_range(repo, query.range.as_deref())?;
+    let total = oids.len();
+    let git_dir = repo.path().to_path_buf();
+
+    let mut matches = Vec::new();
+    let mut scanned = 0;
+    for batch in oids.chunks(SEARCH_BATCH_SIZE) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // git2 repositories can't be shared between threads, so each worker opens its own
+        let found: Vec<Option<CommitInfo>> = batch
+            .par_iter()
+            .map_init(
+                || Repository::open(&git_dir),
+                |worker_repo, oid| -> Result<Option<CommitInfo>, JanusError> {
+                    if cancel.load(Ordering::Relaxed) {
+                        return Ok(None);
+                    }
+                    let worker_repo = worker_repo.as_ref().map_err(|e| JanusError::GitError(e.to_string()))?;
+                    let commit = worker_repo.find_commit(*oid)?;
+                    if matcher.matches(worker_repo, &commit)? {
+                        Ok(Some(commit_to_info(&commit)?))
+                    } else {
+                        Ok(None)
+                    }
+                },
+            )
+            .collect::<Result<_, _>>()?;
+
+        scanned += batch.len();
+        let mut batch_matches: Vec<CommitInfo> = found.into_iter().flatten().collect();
+        batch_matches.truncate(limit - matches.len());
+        on_progress(&CommitSearchProgress {
+            search_id: search_id.to_string(),
+            matches: batch_matches.clone(),
+            scanned,
+            total,
+        });
+        matches.extend(batch_matches);
+
+        if matches.len() >= limit {
+            break;
+        }
+    }
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    let truncated = matches.len() >= limit && scanned < total;
+    Ok(CommitSearchResult { matches, scanned, total, cancelled, truncated })
+}
+
+/// Searches commit history, streaming matches with `SEARCH_PROGRESS_EVENT`.
+/// Cancel it with `cancel_operation(search_id)`.
+#[tauri::command(async)]
+pub fn search_commits(
+    app: AppHandle,
+    repo_path: String,
+    search_id: String,
+    query: CommitSearchQuery,
+) -> Result<CommitSearchResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let token = CancelToken::register(&search_id)?;
+
+    search(&repo, &search_id, &query, token.flag(), &|progress| {
+        if let Err(e) = app.emit(SEARCH_PROGRESS_EVENT, progress.clone()) {
+            warn!("Failed to emit {} event: {}", SEARCH_PROGRESS_EVENT, e);
+        }
+    })
+}
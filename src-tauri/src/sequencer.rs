@@ -0,0 +1,658 @@
+use crate::error::JanusError;
+use crate::git::{self, append_trailers, conflicted_paths, open_repo};
+use crate::interactive_rebase;
+use crate::journal;
+use git2::build::CheckoutBuilder;
+use git2::{Commit, Delta, Oid, Repository, RepositoryState, ResetType, StatusOptions};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// What a sequencer step does with its commit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SequencerAction {
+    Pick,
+    Revert,
+}
+
+impl SequencerAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SequencerAction::Pick => "pick",
+            SequencerAction::Revert => "revert",
+        }
+    }
+
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "pick" | "p" => Some(SequencerAction::Pick),
+            "revert" => Some(SequencerAction::Revert),
+            _ => None,
+        }
+    }
+}
+
+/// One line of the sequencer todo list
+#[derive(Debug, Clone)]
+struct TodoItem {
+    action: SequencerAction,
+    oid: Oid,
+}
+
+/// Options for cherry-picking commits
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CherryPickOptions {
+    /// Parent number (starting at 1) to diff against when picking a merge commit
+    pub mainline: Option<u32>,
+    /// Append a "(cherry picked from commit ...)" line, like `git cherry-pick -x`
+    pub record_origin: bool,
+    /// Apply the changes to the index and working tree without committing
+    pub no_commit: bool,
+}
+
+/// Outcome of a cherry-pick or revert sequence, reported like `MergeResult`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SequencerResult {
+    pub success: bool,
+    pub has_conflicts: bool,
+    pub message: String,
+    pub conflicted_files: Vec<String>,
+    /// Commits created while running this step of the sequence
+    pub created_commits: Vec<String>,
+    /// The commit being applied when the sequence stopped
+    pub current_commit: Option<String>,
+    /// Number of commits still to apply, including the current one
+    pub remaining: usize,
+}
+
+/// Progress of a paused cherry-pick or revert sequence
+#[derive(Debug)]
+pub(crate) struct SequenceProgress {
+    pub action: SequencerAction,
+    pub current_commit: Oid,
+    /// Number of commits still to apply, including the current one
+    pub remaining: usize,
+}
+
+/// Persistent sequencer state, stored in `.git/sequencer` like git's own
+struct SequencerState {
+    dir: PathBuf,
+    head: Oid,
+    todo: Vec<TodoItem>,
+    /// Reverts share these options; `record_origin` only applies to picks
+    options: CherryPickOptions,
+    /// In no-commit mode, the staged tree before the current step, which skipping it returns to
+    staged: Option<Oid>,
+}
+
+impl SequencerState {
+    fn dir(repo: &Repository) -> PathBuf {
+        repo.path().join("sequencer")
+    }
+
+    fn exists(repo: &Repository) -> bool {
+        Self::dir(repo).join("todo").exists()
+    }
+
+    fn load(repo: &Repository) -> Result<Self, JanusError> {
+        let dir = Self::dir(repo);
+        if !dir.join("todo").exists() {
+            return Err(JanusError::ValidationError("No cherry-pick or revert is in progress".to_string()));
+        }
+
+        let head = fs::read_to_string(dir.join("head"))?;
+        let head = Oid::from_str(head.trim())
+            .map_err(|e| JanusError::ParseError(format!("Invalid sequencer head: {}", e)))?;
+
+        let mut todo = Vec::new();
+        for line in fs::read_to_string(dir.join("todo"))?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let action = words.next().and_then(SequencerAction::parse);
+            let oid = words.next().and_then(|word| repo.revparse_single(word).ok()).map(|obj| obj.id());
+            match (action, oid) {
+                (Some(action), Some(oid)) => todo.push(TodoItem { action, oid }),
+                _ => return Err(JanusError::ParseError(format!("Invalid sequencer todo line: {}", line))),
+            }
+        }
+
+        let mut options = CherryPickOptions::default();
+        let opts_path = dir.join("opts");
+        if opts_path.exists() {
+            let config = git2::Config::open(&opts_path)?;
+            options.record_origin = config.get_bool("options.record-origin").unwrap_or(false);
+            options.no_commit = config.get_bool("options.no-commit").unwrap_or(false);
+            options.mainline = config.get_i32("options.mainline").ok().map(|m| m as u32);
+        }
+
+        let staged = fs::read_to_string(dir.join("staged")).ok().and_then(|id| Oid::from_str(id.trim()).ok());
+
+        Ok(Self { dir, head, todo, options, staged })
+    }
+
+    fn save(&self, repo: &Repository) -> Result<(), JanusError> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join("head"), format!("{}\n", self.head))?;
+
+        let mut todo = String::new();
+        for item in &self.todo {
+            let summary = repo
+                .find_commit(item.oid)
+                .ok()
+                .and_then(|c| c.summary().map(|s| s.to_string()))
+                .unwrap_or_default();
+            todo.push_str(&format!("{} {} {}\n", item.action.as_str(), item.oid, summary));
+        }
+        fs::write(self.dir.join("todo"), todo)?;
+        if let Some(staged) = self.staged {
+            fs::write(self.dir.join("staged"), format!("{}\n", staged))?;
+        }
+
+        // Written in git-config format so `git cherry-pick --continue` understands it too
+        let opts_path = self.dir.join("opts");
+        if opts_path.exists() {
+            fs::remove_file(&opts_path)?;
+        }
+        let mut config = git2::Config::open(&opts_path)?;
+        if self.options.record_origin {
+            config.set_bool("options.record-origin", true)?;
+        }
+        if self.options.no_commit {
+            config.set_bool("options.no-commit", true)?;
+        }
+        if let Some(mainline) = self.options.mainline {
+            config.set_i32("options.mainline", mainline as i32)?;
+        }
+
+        Ok(())
+    }
+
+    fn remove(&self) -> Result<(), JanusError> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Removes the per-step state files without touching `.git/sequencer`.
+/// `Repository::cleanup_state` would delete the whole sequence.
+pub(crate) fn clear_step_state(repo: &Repository) -> Result<(), JanusError> {
+    for name in ["CHERRY_PICK_HEAD", "REVERT_HEAD", "MERGE_MSG"] {
+        let path = repo.path().join(name);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fails if tracked files have uncommitted changes that a pick could overwrite
+fn ensure_clean_worktree(repo: &Repository) -> Result<(), JanusError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+
+    let statuses = repo.statuses(Some(&mut options)).map_err(|e| {
+        error!("Failed to get repository status: {}", e);
+        JanusError::GitError(format!("Failed to get repository status: {}", e))
+    })?;
+
+    if !statuses.is_empty() {
+        return Err(JanusError::GitError(
+            "There are uncommitted changes. Please commit or stash them first.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn find_commit<'r>(repo: &'r Repository, oid: Oid) -> Result<Commit<'r>, JanusError> {
+    repo.find_commit(oid).map_err(|e| {
+        error!("Failed to find commit {}: {}", oid, e);
+        JanusError::GitError(format!("Failed to find commit {}: {}", oid, e))
+    })
+}
+
+fn head_commit(repo: &Repository) -> Result<Commit<'_>, JanusError> {
+    repo.head().and_then(|head| head.peel_to_commit()).map_err(|e| {
+        error!("Failed to get HEAD commit: {}", e);
+        JanusError::GitError(format!("Failed to get HEAD commit: {}", e))
+    })
+}
+
+/// Checks that a merge has a mainline parent to apply against, and that the parent exists.
+/// Like `git cherry-pick -m`, a mainline does not stop other commits of a batch being applied.
+fn validate_mainline(commit: &Commit, mainline: Option<u32>) -> Result<(), JanusError> {
+    match (commit.parent_count(), mainline) {
+        (count, None) if count > 1 => Err(JanusError::ValidationError(format!(
+            "Commit {} is a merge but no mainline parent was given", commit.id()
+        ))),
+        (count, Some(mainline)) if count > 1 && (mainline == 0 || mainline as usize > count) => Err(JanusError::ValidationError(format!(
+            "Commit {} does not have parent {}", commit.id(), mainline
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// The mainline to use for a commit, which only means anything for merges
+fn mainline_for(commit: &Commit, mainline: Option<u32>) -> Option<u32> {
+    mainline.filter(|_| commit.parent_count() > 1)
+}
+
+enum StepOutcome {
+    Applied(Option<Oid>),
+    Conflicts(Vec<String>),
+}
+
+/// Merges a todo item into the index and working tree without moving HEAD, so the changes of a
+/// no-commit sequence build up on top of each other like `git cherry-pick -n`
+fn apply_to_index(repo: &Repository, item: &TodoItem, commit: &Commit, mainline: Option<u32>) -> Result<StepOutcome, JanusError> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let ours = repo.find_tree(index.write_tree()?)?;
+
+    // A root commit is applied against the empty tree
+    let parent = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(mainline.unwrap_or(1) as usize - 1)?),
+    };
+    let parent = match parent {
+        Some(parent) => parent.tree()?,
+        None => repo.find_tree(repo.treebuilder(None)?.write()?)?,
+    };
+    let (ancestor, theirs) = match item.action {
+        SequencerAction::Pick => (parent, commit.tree()?),
+        SequencerAction::Revert => (commit.tree()?, parent),
+    };
+    let mut merged = repo.merge_trees(&ancestor, &ours, &theirs, None)?;
+
+    // The working tree matches what earlier steps staged, so only untracked files need protecting
+    let workdir = repo.workdir().ok_or_else(|| JanusError::GitError("Repository has no working tree".to_string()))?;
+    for delta in repo.diff_tree_to_index(Some(&ours), Some(&merged), None)?.deltas() {
+        let added = delta.status() == Delta::Added;
+        if let Some(path) = delta.new_file().path().filter(|path| added && workdir.join(path).exists()) {
+            return Err(JanusError::GitError(format!(
+                "Untracked file {} would be overwritten. Please move or remove it first.",
+                path.display()
+            )));
+        }
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().allow_conflicts(true);
+    repo.checkout_index(Some(&mut merged), Some(&mut checkout)).map_err(|e| {
+        error!("Failed to {} commit {}: {}", item.action.as_str(), commit.id(), e);
+        JanusError::GitError(format!("Failed to {} commit {}: {}", item.action.as_str(), commit.id(), e))
+    })?;
+
+    if merged.has_conflicts() {
+        return Ok(StepOutcome::Conflicts(conflicted_paths(&merged)?));
+    }
+    Ok(StepOutcome::Applied(None))
+}
+
+/// Applies one todo item to the working tree and commits it if it applied cleanly
+fn apply_step(repo: &Repository, item: &TodoItem, options: &CherryPickOptions) -> Result<StepOutcome, JanusError> {
+    let commit = find_commit(repo, item.oid)?;
+
+    validate_mainline(&commit, options.mainline)?;
+    let mainline = mainline_for(&commit, options.mainline);
+    if options.no_commit {
+        return apply_to_index(repo, item, &commit, mainline);
+    }
+
+    match item.action {
+        SequencerAction::Pick => {
+            let mut pick_options = git2::CherrypickOptions::new();
+            if let Some(mainline) = mainline {
+                pick_options.mainline(mainline);
+            }
+            repo.cherrypick(&commit, Some(&mut pick_options))
+        }
+        SequencerAction::Revert => {
+            let mut revert_options = git2::RevertOptions::new();
+            if let Some(mainline) = mainline {
+                revert_options.mainline(mainline);
+            }
+            repo.revert(&commit, Some(&mut revert_options))
+        }
+    }
+    .map_err(|e| {
+        error!("Failed to {} commit {}: {}", item.action.as_str(), commit.id(), e);
+        JanusError::GitError(format!("Failed to {} commit {}: {}", item.action.as_str(), commit.id(), e))
+    })?;
+
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        return Ok(StepOutcome::Conflicts(conflicted_paths(&index)?));
+    }
+
+    commit_step(repo, item, options).map(StepOutcome::Applied)
+}
+
+/// Commits the index for the current todo item.
+/// Returns None if the change was already present and nothing was committed.
+fn commit_step(repo: &Repository, item: &TodoItem, options: &CherryPickOptions) -> Result<Option<Oid>, JanusError> {
+    let commit = find_commit(repo, item.oid)?;
+    let head = head_commit(repo)?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(JanusError::ValidationError(
+            "Resolve all conflicts and stage the results before continuing".to_string(),
+        ));
+    }
+    if options.no_commit {
+        clear_step_state(repo)?;
+        return Ok(None);
+    }
+    let tree_id = index.write_tree()?;
+    if tree_id == head.tree_id() {
+        info!("Commit {} is already applied, skipping it", commit.id());
+        clear_step_state(repo)?;
+        return Ok(None);
+    }
+    let tree = repo.find_tree(tree_id)?;
+
+    let original_message = commit.message().unwrap_or("");
+    let message = match item.action {
+        SequencerAction::Pick if options.record_origin => {
+            append_trailers(original_message, &[format!("(cherry picked from commit {})", commit.id())])
+        }
+        SequencerAction::Pick => original_message.to_string(),
+        SequencerAction::Revert => revert_message(&commit, mainline_for(&commit, options.mainline)),
+    };
+    let message = git2::message_prettify(&message, None)?;
+
+    // Picks keep the original author; a revert is new work by the committer
+    let committer = repo.signature()?;
+    let author = match item.action {
+        SequencerAction::Pick => commit.author(),
+        SequencerAction::Revert => committer.clone(),
+    };
+    let oid = git::write_commit(repo, &author, &committer, &message, &tree, &[&head])?;
+    clear_step_state(repo)?;
+
+    Ok(Some(oid))
+}
+
+/// Builds the standard `git revert` message for a commit
+fn revert_message(commit: &Commit, mainline: Option<u32>) -> String {
+    let summary = commit.summary().unwrap_or("");
+    let mainline_parent = mainline.and_then(|m| commit.parent_id(m as usize - 1).ok());
+
+    match mainline_parent {
+        Some(parent) => format!(
+            "Revert \"{}\"\n\nThis reverts commit {}, reversing\nchanges made to {}.\n",
+            summary, commit.id(), parent
+        ),
+        None => format!("Revert \"{}\"\n\nThis reverts commit {}.\n", summary, commit.id()),
+    }
+}
+
+/// Runs the remaining todo items until the list is empty or a step conflicts
+fn run_sequence(repo: &Repository, mut state: SequencerState, mut created: Vec<String>) -> Result<SequencerResult, JanusError> {
+    while let Some(item) = state.todo.first().cloned() {
+        if state.options.no_commit {
+            let mut index = repo.index()?;
+            index.read(false)?;
+            state.staged = Some(index.write_tree()?);
+        }
+        match apply_step(repo, &item, &state.options)? {
+            StepOutcome::Conflicts(conflicted_files) => {
+                state.save(repo)?;
+                info!("Stopped at {} with conflicts", item.oid);
+                return Ok(SequencerResult {
+                    success: false,
+                    has_conflicts: true,
+                    message: format!("Could not apply {}: resolve the conflicts, then continue, skip or abort", item.oid),
+                    conflicted_files,
+                    created_commits: created,
+                    current_commit: Some(item.oid.to_string()),
+                    remaining: state.todo.len(),
+                });
+            }
+            StepOutcome::Applied(oid) => {
+                created.extend(oid.map(|oid| oid.to_string()));
+                state.todo.remove(0);
+                state.save(repo)?;
+            }
+        }
+    }
+
+    finish_sequence(repo, &state)?;
+
+    let message = if state.options.no_commit {
+        "Changes were applied to the index without committing".to_string()
+    } else {
+        format!("Created {} commit(s)", created.len())
+    };
+
+    Ok(SequencerResult {
+        success: true,
+        has_conflicts: false,
+        message,
+        conflicted_files: Vec::new(),
+        created_commits: created,
+        current_commit: None,
+        remaining: 0,
+    })
+}
+
+/// Ends a completed sequence
+fn finish_sequence(repo: &Repository, state: &SequencerState) -> Result<(), JanusError> {
+    clear_step_state(repo)?;
+    state.remove()
+}
+
+/// Saves the todo list and runs it. Callers check `ensure_can_start` first.
+fn start_sequence(repo: &Repository, todo: Vec<TodoItem>, options: CherryPickOptions) -> Result<SequencerResult, JanusError> {
+    let state = SequencerState {
+        dir: SequencerState::dir(repo),
+        head: head_commit(repo)?.id(),
+        todo,
+        options,
+        staged: None,
+    };
+    state.save(repo)?;
+
+    run_sequence(repo, state, Vec::new())
+}
+
+/// Reads the saved todo list, or returns None when no sequence is in progress
+pub(crate) fn sequence_progress(repo: &Repository) -> Result<Option<SequenceProgress>, JanusError> {
+    if !SequencerState::exists(repo) {
+        return Ok(None);
+    }
+
+    let state = SequencerState::load(repo)?;
+    Ok(state.todo.first().map(|item| SequenceProgress {
+        action: item.action,
+        current_commit: item.oid,
+        remaining: state.todo.len(),
+    }))
+}
+
+/// Fails unless the repository is idle and its tracked files are unmodified
+pub(crate) fn ensure_can_start(repo: &Repository) -> Result<(), JanusError> {
+    if repo.state() != RepositoryState::Clean || SequencerState::exists(repo) || interactive_rebase::in_progress(repo) {
+        return Err(JanusError::GitError(
+            "Another operation is in progress. Continue or abort it first.".to_string(),
+        ));
+    }
+    ensure_clean_worktree(repo)
+}
+
+pub(crate) fn resolve_commit<'r>(repo: &'r Repository, spec: &str) -> Result<Commit<'r>, JanusError> {
+    repo.revparse_single(spec).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to find commit {}: {}", spec, e);
+        JanusError::GitError(format!("Failed to find commit {}: {}", spec, e))
+    })
+}
+
+/// Fails unless the sequence in progress is made of `action` steps, so continuing a revert
+/// as a cherry-pick, or the other way round, is refused
+fn ensure_action(repo: &Repository, action: SequencerAction) -> Result<(), JanusError> {
+    let name = |action| match action {
+        SequencerAction::Pick => "cherry-pick",
+        SequencerAction::Revert => "revert",
+    };
+    let state = SequencerState::load(repo)?;
+    match state.todo.first() {
+        Some(item) if item.action != action => Err(JanusError::ValidationError(format!(
+            "A {} is in progress, not a {}", name(item.action), name(action)
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Commits the resolved current step and carries on with the sequence
+fn continue_sequence(repo: &Repository) -> Result<SequencerResult, JanusError> {
+    let mut state = SequencerState::load(repo)?;
+
+    let mut created = Vec::new();
+    if let Some(item) = state.todo.first().cloned() {
+        created.extend(commit_step(repo, &item, &state.options)?.map(|oid| oid.to_string()));
+        state.todo.remove(0);
+        state.save(repo)?;
+    }
+
+    run_sequence(repo, state, created)
+}
+
+/// Abandons the sequence and returns to the commit it started from
+fn abort_sequence(repo: &Repository) -> Result<(), JanusError> {
+    let state = SequencerState::load(repo)?;
+
+    let original = repo.find_object(state.head, None)?;
+    repo.reset(&original, ResetType::Hard, None).map_err(|e| {
+        error!("Failed to reset to {}: {}", state.head, e);
+        JanusError::GitError(format!("Failed to reset to {}: {}", state.head, e))
+    })?;
+
+    repo.cleanup_state()?;
+    state.remove()?;
+
+    info!("Aborted sequence, HEAD restored to {}", state.head);
+    Ok(())
+}
+
+/// Cherry-picks commits onto the current branch in order
+#[tauri::command]
+pub fn cherry_pick(repo_path: String, commits: Vec<String>, options: Option<CherryPickOptions>) -> Result<SequencerResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let options = options.unwrap_or_default();
+
+    if commits.is_empty() {
+        return Err(JanusError::ValidationError("No commits to cherry-pick".to_string()));
+    }
+    ensure_can_start(&repo)?;
+
+    let mut todo = Vec::new();
+    for spec in &commits {
+        let commit = resolve_commit(&repo, spec)?;
+        validate_mainline(&commit, options.mainline)?;
+        todo.push(TodoItem { action: SequencerAction::Pick, oid: commit.id() });
+    }
+
+    // Begun only once validated, so a refused pick leaves the journal alone
+    let _journal = journal::Recorder::begin(&repo, "cherry_pick", &format!("Cherry-pick {}", commits.join(", ")), git::ResetMode::Hard);
+    info!("Cherry-picking {} commit(s)", todo.len());
+    start_sequence(&repo, todo, options)
+}
+
+/// Commits the resolved cherry-pick and carries on with the sequence
+#[tauri::command]
+pub fn cherry_pick_continue(repo_path: String) -> Result<SequencerResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    ensure_action(&repo, SequencerAction::Pick)?;
+    let _journal = journal::Recorder::begin(&repo, "cherry_pick_continue", "Continue cherry-pick", git::ResetMode::Hard);
+    continue_sequence(&repo)
+}
+
+/// Drops the current step, discarding its changes, and carries on with the sequence
+#[tauri::command]
+pub fn cherry_pick_skip(repo_path: String) -> Result<SequencerResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let _journal = journal::Recorder::begin(&repo, "cherry_pick_skip", "Skip cherry-pick step", git::ResetMode::Hard);
+    let mut state = SequencerState::load(&repo)?;
+
+    // In no-commit mode HEAD never moves, so go back to what the earlier steps staged
+    let head = head_commit(&repo)?;
+    let discarded = match state.staged.filter(|_| state.options.no_commit) {
+        Some(staged) => repo.find_tree(staged).and_then(|tree| {
+            repo.checkout_tree(tree.as_object(), Some(CheckoutBuilder::new().force()))?;
+            let mut index = repo.index()?;
+            index.read_tree(&tree)?;
+            index.write()
+        }),
+        None => repo.reset(head.as_object(), ResetType::Hard, None),
+    };
+    discarded.map_err(|e| {
+        error!("Failed to discard the current step: {}", e);
+        JanusError::GitError(format!("Failed to discard the current step: {}", e))
+    })?;
+    clear_step_state(&repo)?;
+
+    if !state.todo.is_empty() {
+        state.todo.remove(0);
+        state.save(&repo)?;
+    }
+
+    run_sequence(&repo, state, Vec::new())
+}
+
+/// Abandons the cherry-pick and returns to the commit it started from
+#[tauri::command]
+pub fn cherry_pick_abort(repo_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    abort_sequence(&repo)
+}
+
+/// Reverts a commit, creating a commit that undoes its changes.
+/// With `no_commit` the inverse changes are left staged instead.
+#[tauri::command]
+pub fn revert_commit(repo_path: String, commit_id: String, mainline: Option<u32>, no_commit: Option<bool>) -> Result<SequencerResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    ensure_can_start(&repo)?;
+
+    let commit = resolve_commit(&repo, &commit_id)?;
+    validate_mainline(&commit, mainline)?;
+    if mainline.is_some() && commit.parent_count() <= 1 {
+        return Err(JanusError::ValidationError(format!(
+            "Mainline was specified but commit {} is not a merge", commit.id()
+        )));
+    }
+
+    let options = CherryPickOptions {
+        mainline,
+        record_origin: false,
+        no_commit: no_commit.unwrap_or(false),
+    };
+
+    let _journal = journal::Recorder::begin(&repo, "revert_commit", &format!("Revert {}", commit_id), git::ResetMode::Hard);
+    info!("Reverting commit {}", commit.id());
+    start_sequence(&repo, vec![TodoItem { action: SequencerAction::Revert, oid: commit.id() }], options)
+}
+
+/// Commits the resolved revert
+#[tauri::command]
+pub fn revert_continue(repo_path: String) -> Result<SequencerResult, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    ensure_action(&repo, SequencerAction::Revert)?;
+    let _journal = journal::Recorder::begin(&repo, "revert_continue", "Continue revert", git::ResetMode::Hard);
+    continue_sequence(&repo)
+}
+
+/// Abandons the revert and returns to the commit it started from
+#[tauri::command]
+pub fn revert_abort(repo_path: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    abort_sequence(&repo)
+}
@@ -0,0 +1,421 @@
+use crate::error::JanusError;
+use git2::{Oid, Repository};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Namespace used for SSH signatures on commits and tags, as git does
+const SSH_NAMESPACE: &str = "git";
+
+/// The kind of key used to sign objects, from `gpg.format`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningFormat {
+    Openpgp,
+    Ssh,
+    X509,
+}
+
+/// Signing settings read from the repository configuration
+#[derive(Debug, Clone)]
+pub struct SigningConfig {
+    pub format: SigningFormat,
+    pub key: Option<String>,
+    pub program: String,
+    pub allowed_signers: Option<PathBuf>,
+}
+
+impl SigningConfig {
+    /// Reads `gpg.format`, `user.signingkey`, the signing program and `gpg.ssh.allowedSignersFile`
+    pub fn from_repo(repo: &Repository) -> Result<Self, JanusError> {
+        let config = repo.config()?;
+
+        let format = match config.get_string("gpg.format").ok().as_deref() {
+            None | Some("openpgp") => SigningFormat::Openpgp,
+            Some("ssh") => SigningFormat::Ssh,
+            Some("x509") => SigningFormat::X509,
+            Some(other) => {
+                return Err(JanusError::ConfigError(format!("Unsupported gpg.format: {}", other)));
+            }
+        };
+
+        let program = match format {
+            SigningFormat::Openpgp => config
+                .get_string("gpg.openpgp.program")
+                .or_else(|_| config.get_string("gpg.program"))
+                .unwrap_or_else(|_| "gpg".to_string()),
+            SigningFormat::Ssh => config
+                .get_string("gpg.ssh.program")
+                .unwrap_or_else(|_| "ssh-keygen".to_string()),
+            SigningFormat::X509 => config
+                .get_string("gpg.x509.program")
+                .unwrap_or_else(|_| "gpgsm".to_string()),
+        };
+
+        Ok(Self {
+            format,
+            key: config.get_string("user.signingkey").ok(),
+            program,
+            allowed_signers: config.get_path("gpg.ssh.allowedSignersFile").ok(),
+        })
+    }
+}
+
+/// Returns true if `commit.gpgsign` asks for commits to be signed
+pub fn should_sign_commits(repo: &Repository) -> bool {
+    repo.config()
+        .and_then(|config| config.get_bool("commit.gpgsign"))
+        .unwrap_or(false)
+}
+
+/// Signs an object buffer, returning the armored signature
+pub fn sign_buffer(repo: &Repository, buffer: &str) -> Result<String, JanusError> {
+    let config = SigningConfig::from_repo(repo)?;
+
+    match config.format {
+        SigningFormat::Ssh => sign_ssh(&config, buffer),
+        SigningFormat::Openpgp | SigningFormat::X509 => {
+            // Without an explicit key, gpg picks one matching the committer identity
+            let key = match config.key.clone() {
+                Some(key) => key,
+                None => {
+                    let signature = repo.signature()?;
+                    format!("{} <{}>", signature.name().unwrap_or(""), signature.email().unwrap_or(""))
+                }
+            };
+            sign_gpg(&config, &key, buffer)
+        }
+    }
+}
+
+fn sign_gpg(config: &SigningConfig, key: &str, buffer: &str) -> Result<String, JanusError> {
+    let output = run_with_stdin(
+        Command::new(&config.program).args(["--status-fd=2", "-bsau", key]),
+        buffer,
+    )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        error!("{} failed to sign the data: {}", config.program, stderr);
+        return Err(JanusError::GitError(format!("Failed to sign with {}: {}", config.program, stderr)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn sign_ssh(config: &SigningConfig, buffer: &str) -> Result<String, JanusError> {
+    let key = config
+        .key
+        .as_deref()
+        .ok_or_else(|| JanusError::ConfigError("user.signingkey must be set for SSH signing".to_string()))?;
+
+    let scratch = ScratchDir::new("sign")?;
+    let data_path = scratch.write("buffer", buffer)?;
+
+    // A literal public key is resolved to its private half through ssh-agent
+    let literal_key = key.strip_prefix("key::").or_else(|| key.starts_with("ssh-").then_some(key));
+    let mut command = Command::new(&config.program);
+    command.args(["-Y", "sign", "-n", SSH_NAMESPACE]);
+    match literal_key {
+        Some(public_key) => {
+            let key_path = scratch.write("signing_key.pub", public_key)?;
+            command.arg("-f").arg(key_path).arg("-U");
+        }
+        None => {
+            command.arg("-f").arg(expand_home(key));
+        }
+    }
+
+    let output = command.arg(&data_path).output().map_err(|e| {
+        error!("Failed to execute {}: {}", config.program, e);
+        JanusError::IoError(format!("Failed to execute {}: {}", config.program, e))
+    })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        error!("{} failed to sign the data: {}", config.program, stderr);
+        return Err(JanusError::GitError(format!("Failed to sign with {}: {}", config.program, stderr)));
+    }
+
+    let signature_path = data_path.with_extension("sig");
+    fs::read_to_string(&signature_path)
+        .map_err(|e| JanusError::IoError(format!("Failed to read SSH signature: {}", e)))
+}
+
+/// Outcome of checking a signature
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// The object carries no signature
+    Unsigned,
+    /// The signature is valid and made by a trusted key
+    Good,
+    /// The signature does not match the signed data
+    Bad,
+    /// The signature may be valid, but the key is not known or not allowed
+    UnknownKey,
+    /// The signature is valid, but the key is not trusted
+    Untrusted,
+    /// The signature or the key that made it has expired
+    Expired,
+    /// The key that made the signature has been revoked
+    Revoked,
+    /// The signature could not be checked
+    Error,
+}
+
+/// Verification result reported with commit and tag details
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignatureInfo {
+    pub status: SignatureStatus,
+    pub format: Option<SigningFormat>,
+    pub signer: Option<String>,
+    pub key: Option<String>,
+    pub details: String,
+}
+
+impl SignatureInfo {
+    fn unsigned() -> Self {
+        Self {
+            status: SignatureStatus::Unsigned,
+            format: None,
+            signer: None,
+            key: None,
+            details: String::new(),
+        }
+    }
+}
+
+/// Verifies the signature on a commit
+pub fn verify_commit(repo: &Repository, oid: Oid) -> Result<SignatureInfo, JanusError> {
+    match repo.extract_signature(&oid, None) {
+        Ok((signature, signed_data)) => {
+            let signature = String::from_utf8_lossy(&signature).to_string();
+            let signed_data = String::from_utf8_lossy(&signed_data).to_string();
+            verify_signature(repo, &signature, &signed_data)
+        }
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(SignatureInfo::unsigned()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Verifies the signature on an annotated tag.
+/// Tag signatures are appended to the tag message rather than stored in a header.
+pub fn verify_tag(repo: &Repository, oid: Oid) -> Result<SignatureInfo, JanusError> {
+    let odb = repo.odb()?;
+    let object = odb.read(oid)?;
+    let content = String::from_utf8_lossy(object.data()).to_string();
+
+    let signature_start = ["-----BEGIN PGP SIGNATURE-----", "-----BEGIN SSH SIGNATURE-----", "-----BEGIN SIGNED MESSAGE-----"]
+        .iter()
+        .filter_map(|marker| content.find(&format!("\n{}", marker)).map(|i| i + 1))
+        .min();
+
+    match signature_start {
+        Some(start) => verify_signature(repo, &content[start..], &content[..start]),
+        None => Ok(SignatureInfo::unsigned()),
+    }
+}
+
+fn verify_signature(repo: &Repository, signature: &str, signed_data: &str) -> Result<SignatureInfo, JanusError> {
+    let config = SigningConfig::from_repo(repo)?;
+
+    if signature.starts_with("-----BEGIN SSH SIGNATURE-----") {
+        let program = if config.format == SigningFormat::Ssh { config.program.as_str() } else { "ssh-keygen" };
+        verify_ssh(program, config.allowed_signers.as_deref(), signature, signed_data)
+    } else {
+        let (format, program) = if signature.starts_with("-----BEGIN SIGNED MESSAGE-----") {
+            (SigningFormat::X509, if config.format == SigningFormat::X509 { config.program.as_str() } else { "gpgsm" })
+        } else {
+            (SigningFormat::Openpgp, if config.format == SigningFormat::Openpgp { config.program.as_str() } else { "gpg" })
+        };
+        verify_gpg(format, program, signature, signed_data)
+    }
+}
+
+fn verify_ssh(program: &str, allowed_signers: Option<&Path>, signature: &str, signed_data: &str) -> Result<SignatureInfo, JanusError> {
+    let scratch = ScratchDir::new("verify")?;
+    let signature_path = scratch.write("object.sig", signature)?;
+    let mut info = SignatureInfo {
+        format: Some(SigningFormat::Ssh),
+        ..SignatureInfo::unsigned()
+    };
+
+    // Find who the allowed signers file says made this signature
+    let principal = match allowed_signers {
+        Some(allowed) => {
+            let output = Command::new(program)
+                .args(["-Y", "find-principals", "-f"])
+                .arg(expand_home(&allowed.to_string_lossy()))
+                .arg("-s")
+                .arg(&signature_path)
+                .output()
+                .map_err(|e| JanusError::IoError(format!("Failed to execute {}: {}", program, e)))?;
+            String::from_utf8_lossy(&output.stdout).lines().next().map(|line| line.trim().to_string())
+        }
+        None => None,
+    };
+
+    let (output, status_if_ok) = match (allowed_signers, principal) {
+        (Some(allowed), Some(principal)) => {
+            info.signer = Some(principal.clone());
+            let output = run_with_stdin(
+                Command::new(program)
+                    .args(["-Y", "verify", "-n", SSH_NAMESPACE, "-f"])
+                    .arg(expand_home(&allowed.to_string_lossy()))
+                    .arg("-I")
+                    .arg(&principal)
+                    .arg("-s")
+                    .arg(&signature_path),
+                signed_data,
+            )?;
+            (output, SignatureStatus::Good)
+        }
+        _ => {
+            // No trusted principal: check the signature is at least intact
+            let output = run_with_stdin(
+                Command::new(program)
+                    .args(["-Y", "check-novalidate", "-n", SSH_NAMESPACE, "-s"])
+                    .arg(&signature_path),
+                signed_data,
+            )?;
+            (output, SignatureStatus::UnknownKey)
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    info.details = format!("{}{}", stdout, stderr).trim().to_string();
+    info.key = stdout
+        .split_whitespace()
+        .find(|word| word.starts_with("SHA256:"))
+        .map(|key| key.to_string());
+    info.status = if output.status.success() { status_if_ok } else { SignatureStatus::Bad };
+
+    Ok(info)
+}
+
+fn verify_gpg(format: SigningFormat, program: &str, signature: &str, signed_data: &str) -> Result<SignatureInfo, JanusError> {
+    let scratch = ScratchDir::new("verify")?;
+    let signature_path = scratch.write("object.sig", signature)?;
+
+    let output = run_with_stdin(
+        Command::new(program)
+            .args(["--status-fd=1", "--verify"])
+            .arg(&signature_path)
+            .arg("-"),
+        signed_data,
+    )?;
+
+    let mut info = SignatureInfo {
+        format: Some(format),
+        status: SignatureStatus::Error,
+        ..SignatureInfo::unsigned()
+    };
+    info.details = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+    // Interpret the machine-readable status lines gpg prints on --status-fd.
+    // A good signature only counts when the key is fully trusted, like the SSH allowed signers.
+    let mut trusted = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut words = line.trim_start_matches("[GNUPG:] ").splitn(3, ' ');
+        let keyword = words.next().unwrap_or("");
+        let key = words.next().map(|k| k.to_string());
+        let signer = words.next().map(|s| s.to_string());
+
+        match keyword {
+            "GOODSIG" | "EXPSIG" | "EXPKEYSIG" | "REVKEYSIG" => {
+                info.status = match keyword {
+                    "GOODSIG" => SignatureStatus::Good,
+                    "REVKEYSIG" => SignatureStatus::Revoked,
+                    _ => SignatureStatus::Expired,
+                };
+                info.key = key;
+                info.signer = signer;
+            }
+            "TRUST_FULLY" | "TRUST_ULTIMATE" => trusted = true,
+            "BADSIG" => {
+                info.status = SignatureStatus::Bad;
+                info.key = key;
+                info.signer = signer;
+            }
+            "ERRSIG" | "NO_PUBKEY" if info.status == SignatureStatus::Error => {
+                info.status = SignatureStatus::UnknownKey;
+                info.key = key;
+            }
+            _ => {}
+        }
+    }
+    if info.status == SignatureStatus::Good && !trusted {
+        info.status = SignatureStatus::Untrusted;
+    }
+
+    Ok(info)
+}
+
+fn run_with_stdin(command: &mut Command, input: &str) -> Result<Output, JanusError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            error!("Failed to execute {}: {}", program, e);
+            JanusError::IoError(format!("Failed to execute {}: {}", program, e))
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| JanusError::IoError(format!("Failed to write to {}: {}", program, e)))?;
+    }
+
+    child
+        .wait_with_output()
+        .map_err(|e| JanusError::IoError(format!("Failed to wait for {}: {}", program, e)))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Temporary directory for handing data to signing programs, removed on drop
+struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    fn new(purpose: &str) -> Result<Self, JanusError> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("januslens_{}_{}_{}", purpose, std::process::id(), nanos));
+        fs::create_dir_all(&path)
+            .map_err(|e| JanusError::IoError(format!("Failed to create temporary directory: {}", e)))?;
+        Ok(Self { path })
+    }
+
+    fn write(&self, name: &str, content: &str) -> Result<PathBuf, JanusError> {
+        let path = self.path.join(name);
+        fs::write(&path, content)
+            .map_err(|e| JanusError::IoError(format!("Failed to write temporary file: {}", e)))?;
+        Ok(path)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            info!("Failed to remove temporary directory {:?}: {}", self.path, e);
+        }
+    }
+}
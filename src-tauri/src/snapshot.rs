@@ -0,0 +1,252 @@
+use crate::error::JanusError;
+use crate::git::open_repo;
+use git2::{ApplyLocation, ApplyOptions, Commit, Index, IndexEntry, IndexTime, ObjectType, Oid, Repository, StatusOptions};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Namespace holding snapshot refs so they are never garbage collected
+const SNAPSHOT_REF_PREFIX: &str = "refs/januslens/snapshots/";
+
+/// A saved copy of uncommitted work
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub reference: String,
+    pub message: String,
+    pub base_commit: String,
+    pub time: i64,
+}
+
+/// Records the index and working tree as a stash-shaped commit.
+///
+/// Like `git stash`, the snapshot commit has HEAD and an "index on" commit as
+/// parents. Untracked files are stored in the snapshot tree itself rather than
+/// in a third parent. Returns None if there is nothing uncommitted to save.
+pub fn create_snapshot(repo: &Repository, reason: &str) -> Result<Option<Oid>, JanusError> {
+    let head = match repo.head().and_then(|h| h.peel_to_commit()) {
+        Ok(head) => head,
+        // Nothing has been committed yet, so there is no base to snapshot against
+        Err(_) => return Ok(None),
+    };
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let statuses = repo.statuses(Some(&mut options))?;
+    if statuses.is_empty() {
+        return Ok(None);
+    }
+
+    let signature = repo.signature()?;
+    let branch = repo
+        .head()
+        .ok()
+        .and_then(|h| h.shorthand().map(|s| s.to_string()))
+        .unwrap_or_else(|| "(no branch)".to_string());
+    let short_id = head.id().to_string()[..8].to_string();
+    let summary = head.summary().unwrap_or("");
+
+    // The "index on" commit records what was staged
+    let index_tree_id = staged_tree(repo)?;
+    let index_tree = repo.find_tree(index_tree_id)?;
+    let index_commit_id = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &format!("index on {}: {} {}", branch, short_id, summary),
+        &index_tree,
+        &[&head],
+    )?;
+    let index_commit = repo.find_commit(index_commit_id)?;
+
+    // Layer the working tree changes over the staged tree
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| JanusError::GitError("Cannot snapshot a bare repository".to_string()))?;
+    let mut worktree_index = Index::new()?;
+    worktree_index.read_tree(&index_tree)?;
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        let Some(path) = entry.path() else { continue };
+        let changed = status.is_wt_new() || status.is_wt_modified() || status.is_wt_deleted() || status.is_wt_typechange();
+        // Conflicted files are kept as they are on disk, conflict markers and all
+        if !(changed || status.is_conflicted()) {
+            continue;
+        }
+
+        let full_path = workdir.join(path);
+        match fs::symlink_metadata(&full_path) {
+            Ok(metadata) if metadata.is_file() || metadata.file_type().is_symlink() => {
+                worktree_index.add(&worktree_entry(repo, &full_path, path, &metadata)?)?;
+            }
+            Ok(_) => {}
+            Err(_) => {
+                worktree_index.remove_path(Path::new(path))?;
+            }
+        }
+    }
+
+    let worktree_tree_id = worktree_index.write_tree_to(repo)?;
+    let worktree_tree = repo.find_tree(worktree_tree_id)?;
+    let snapshot_id = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &format!("JanusLens snapshot on {}: {} {}\n\n{}\n", branch, short_id, summary, reason),
+        &worktree_tree,
+        &[&head, &index_commit],
+    )?;
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let reference = format!("{}{}", SNAPSHOT_REF_PREFIX, nanos);
+    repo.reference(&reference, snapshot_id, false, reason)?;
+
+    info!("Saved snapshot {} of uncommitted changes as {}", snapshot_id, reference);
+    Ok(Some(snapshot_id))
+}
+
+/// Writes the index as a tree. A conflicted index cannot be written as it is, so the
+/// "ours" side stands in for each conflicted path, as it does in the working tree.
+fn staged_tree(repo: &Repository) -> Result<Oid, JanusError> {
+    let mut index = repo.index()?;
+    if !index.has_conflicts() {
+        return Ok(index.write_tree()?);
+    }
+
+    let mut staged = Index::new()?;
+    for mut entry in index.iter() {
+        match (entry.flags >> 12) & 0x3 {
+            0 => staged.add(&entry)?,
+            2 => {
+                entry.flags &= !0x3000;
+                staged.add(&entry)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(staged.write_tree_to(repo)?)
+}
+
+fn worktree_entry(repo: &Repository, full_path: &Path, path: &str, metadata: &fs::Metadata) -> Result<IndexEntry, JanusError> {
+    let (id, mode) = if metadata.file_type().is_symlink() {
+        let target = fs::read_link(full_path)?;
+        (repo.blob(target.to_string_lossy().as_bytes())?, 0o120000)
+    } else {
+        (repo.blob_path(full_path)?, if is_executable(metadata) { 0o100755 } else { 0o100644 })
+    };
+
+    Ok(IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: metadata.len() as u32,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+fn snapshot_info(reference: &str, commit: &Commit) -> SnapshotInfo {
+    SnapshotInfo {
+        id: commit.id().to_string(),
+        reference: reference.to_string(),
+        message: commit.message().unwrap_or("").to_string(),
+        base_commit: commit.parent_id(0).map(|id| id.to_string()).unwrap_or_default(),
+        time: commit.time().seconds(),
+    }
+}
+
+/// Lists saved snapshots, newest first
+#[tauri::command]
+pub fn list_snapshots(repo_path: String) -> Result<Vec<SnapshotInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+
+    let mut snapshots = Vec::new();
+    for reference in repo.references_glob(&format!("{}*", SNAPSHOT_REF_PREFIX))? {
+        let reference = reference?;
+        let (Some(name), Ok(commit)) = (reference.name(), reference.peel_to_commit()) else { continue };
+        snapshots.push(snapshot_info(name, &commit));
+    }
+
+    // Ref names end in a creation timestamp, so they sort chronologically
+    snapshots.sort_by(|a, b| b.reference.cmp(&a.reference));
+    Ok(snapshots)
+}
+
+/// Re-applies the changes saved in a snapshot to the working tree. When HEAD is still at
+/// the commit the snapshot was taken on, what was staged is restored to the index as well.
+pub(crate) fn apply_snapshot(repo: &Repository, snapshot_id: &str) -> Result<(), JanusError> {
+    let snapshot = repo.revparse_single(snapshot_id).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to find snapshot {}: {}", snapshot_id, e);
+        JanusError::GitError(format!("Failed to find snapshot {}: {}", snapshot_id, e))
+    })?;
+    let base = snapshot.parent(0).map_err(|e| {
+        JanusError::ValidationError(format!("{} is not a snapshot commit: {}", snapshot_id, e))
+    })?;
+
+    // Staged changes only apply cleanly on top of the commit they were staged against
+    let head = repo.head().ok().and_then(|head| head.target());
+    if let (true, Ok(index_commit)) = (head == Some(base.id()), snapshot.parent(1)) {
+        let staged = repo.diff_tree_to_tree(Some(&base.tree()?), Some(&index_commit.tree()?), None)?;
+        repo.apply(&staged, ApplyLocation::Index, None).map_err(|e| {
+            error!("Failed to restore the index from snapshot {}: {}", snapshot_id, e);
+            JanusError::GitError(format!("Failed to restore the index from snapshot {}: {}", snapshot_id, e))
+        })?;
+    }
+
+    // Apply only what changed relative to the commit the snapshot was taken on
+    let diff = repo.diff_tree_to_tree(Some(&base.tree()?), Some(&snapshot.tree()?), None)?;
+    let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+
+    // Untracked files survive a hard reset, so skip any file already matching the snapshot
+    let mut apply_options = ApplyOptions::new();
+    apply_options.delta_callback(|delta| {
+        let Some(delta) = delta else { return true };
+        let Some(path) = delta.new_file().path() else { return true };
+        Oid::hash_file(ObjectType::Blob, workdir.join(path))
+            .map(|id| id != delta.new_file().id())
+            .unwrap_or(true)
+    });
+
+    repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut apply_options)).map_err(|e| {
+        error!("Failed to apply snapshot {}: {}", snapshot_id, e);
+        JanusError::GitError(format!("Failed to apply snapshot {}: {}", snapshot_id, e))
+    })?;
+
+    info!("Restored snapshot {}", snapshot.id());
+    Ok(())
+}
+
+/// Restores the uncommitted work saved in a snapshot, like `git stash apply --index`.
+/// Untracked files come back untracked.
+#[tauri::command]
+pub fn restore_snapshot(repo_path: String, snapshot_id: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    apply_snapshot(&repo, &snapshot_id)
+}
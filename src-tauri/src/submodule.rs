@@ -0,0 +1,137 @@
+use crate::error::JanusError;
+use crate::git::{open_repo, RepoInfo};
+use crate::paths;
+use git2::{Repository, Submodule, SubmoduleIgnore, SubmoduleStatus, SubmoduleUpdateOptions};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+/// A submodule as recorded by the superproject and as found on disk
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    /// Commit recorded in HEAD
+    pub head_id: Option<String>,
+    /// Commit recorded in the index, which differs from `head_id` once a new one is staged
+    pub index_id: Option<String>,
+    /// Commit checked out in the submodule
+    pub workdir_id: Option<String>,
+    /// True once its URL has been copied into `.git/config`, like `git submodule init`
+    pub initialized: bool,
+    /// True when the submodule has been cloned into the working tree
+    pub checked_out: bool,
+    /// True when the checked out commit is not the recorded one
+    pub out_of_date: bool,
+    /// True when the submodule has uncommitted or untracked changes
+    pub dirty: bool,
+}
+
+fn submodule_info(repo: &Repository, submodule: &Submodule) -> Result<SubmoduleInfo, JanusError> {
+    let name = submodule.name().unwrap_or("").to_string();
+    let status = repo.submodule_status(&name, SubmoduleIgnore::None)?;
+    let initialized = repo.config()?.get_string(&format!("submodule.{}.url", name)).is_ok();
+    let recorded = submodule.index_id().or(submodule.head_id());
+
+    Ok(SubmoduleInfo {
+        path: submodule.path().to_string_lossy().to_string(),
+        url: submodule.url().map(|s| s.to_string()),
+        branch: submodule.branch().map(|s| s.to_string()),
+        head_id: submodule.head_id().map(|oid| oid.to_string()),
+        index_id: submodule.index_id().map(|oid| oid.to_string()),
+        workdir_id: submodule.workdir_id().map(|oid| oid.to_string()),
+        initialized,
+        checked_out: !status.is_wd_uninitialized(),
+        out_of_date: submodule.workdir_id().is_some() && submodule.workdir_id() != recorded,
+        dirty: status.contains(SubmoduleStatus::WD_INDEX_MODIFIED) || status.is_wd_wd_modified() || status.is_wd_untracked(),
+        name,
+    })
+}
+
+/// The submodules at the given paths, or all of them when none are given
+fn selected<'r>(repo: &'r Repository, paths: &[String]) -> Result<Vec<Submodule<'r>>, JanusError> {
+    let wanted: Vec<String> = paths.iter().map(|path| paths::validate_file_path(repo, path)).collect::<Result<_, _>>()?;
+    let submodules = repo.submodules()?;
+
+    for path in &wanted {
+        if !submodules.iter().any(|submodule| submodule.path().to_string_lossy() == path.as_str()) {
+            return Err(JanusError::ValidationError(format!("{} is not a submodule", path)));
+        }
+    }
+    Ok(submodules
+        .into_iter()
+        .filter(|submodule| wanted.is_empty() || wanted.iter().any(|path| submodule.path().to_string_lossy() == path.as_str()))
+        .collect())
+}
+
+fn submodule_error(action: &str, submodule: &Submodule, e: git2::Error) -> JanusError {
+    let path = submodule.path().display();
+    error!("Failed to {} submodule {}: {}", action, path, e);
+    JanusError::GitError(format!("Failed to {} submodule {}: {}", action, path, e))
+}
+
+/// Lists the submodules of the repository
+#[tauri::command]
+pub fn list_submodules(repo_path: String) -> Result<Vec<SubmoduleInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let submodules = repo.submodules()?;
+    submodules.iter().map(|submodule| submodule_info(&repo, submodule)).collect()
+}
+
+/// Copies submodule URLs from `.gitmodules` into `.git/config`, like `git submodule init`
+#[tauri::command]
+pub fn init_submodules(repo_path: String, paths: Option<Vec<String>>) -> Result<Vec<SubmoduleInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    for mut submodule in selected(&repo, &paths.unwrap_or_default())? {
+        submodule.init(false).map_err(|e| submodule_error("initialize", &submodule, e))?;
+        info!("Initialized submodule {}", submodule.path().display());
+    }
+    list_submodules(repo_path)
+}
+
+/// Clones missing submodules and checks out the recorded commits, like `git submodule update`.
+/// With `init`, submodules that were never initialized are initialized first.
+#[tauri::command(async)]
+pub fn update_submodules(repo_path: String, paths: Option<Vec<String>>, init: Option<bool>) -> Result<Vec<SubmoduleInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let init = init.unwrap_or(false);
+    for mut submodule in selected(&repo, &paths.unwrap_or_default())? {
+        let mut options = SubmoduleUpdateOptions::new();
+        submodule.update(init, Some(&mut options)).map_err(|e| submodule_error("update", &submodule, e))?;
+        info!("Updated submodule {}", submodule.path().display());
+    }
+    list_submodules(repo_path)
+}
+
+/// Copies changed URLs from `.gitmodules` into `.git/config` and the submodules' remotes,
+/// like `git submodule sync`
+#[tauri::command]
+pub fn sync_submodules(repo_path: String, paths: Option<Vec<String>>) -> Result<Vec<SubmoduleInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    for mut submodule in selected(&repo, &paths.unwrap_or_default())? {
+        submodule.sync().map_err(|e| submodule_error("sync", &submodule, e))?;
+        info!("Synced submodule {}", submodule.path().display());
+    }
+    list_submodules(repo_path)
+}
+
+/// Resolves a checked out submodule to a repository the other commands can be pointed at.
+/// Unlike `open_repository`, it is not added to the recent repositories.
+#[tauri::command]
+pub fn open_submodule(repo_path: String, path: String) -> Result<RepoInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let submodules = selected(&repo, std::slice::from_ref(&path))?;
+    let submodule = submodules
+        .first()
+        .ok_or_else(|| JanusError::ValidationError(format!("{} is not a submodule", path)))?;
+
+    let sub_repo = submodule.open().map_err(|e| {
+        error!("Failed to open submodule {}: {}", path, e);
+        JanusError::GitError(format!("Submodule {} is not checked out: {}", path, e))
+    })?;
+    let workdir = sub_repo
+        .workdir()
+        .ok_or_else(|| JanusError::GitError(format!("Submodule {} has no working tree", path)))?;
+    RepoInfo::from_path(workdir.to_path_buf())
+}
@@ -9,10 +9,8 @@ pub fn create_test_repository(name: &str) -> PathBuf {
     let temp_dir = std::env::temp_dir();
     let test_dir = temp_dir.join(format!("januslens_test_{}", name));
     
-    // Clean up any previous test repo
-    if test_dir.exists() {
-        fs::remove_dir_all(&test_dir).expect("Failed to remove previous test directory");
-    }
+    // Clean up any previous test repo, along with its undo journal
+    cleanup_test_repository(&test_dir);
     
     fs::create_dir(&test_dir).expect("Failed to create test directory");
     
@@ -69,8 +67,30 @@ pub fn run_git_command(args: &[&str], dir: &PathBuf) {
     }
 }
 
+/// Install an executable hook script into a repository's default hooks directory
+pub fn install_hook(repo_dir: &Path, name: &str, script: &str) {
+    install_hook_in(&repo_dir.join(".git/hooks"), name, script);
+}
+
+/// Install an executable hook script into an arbitrary hooks directory
+pub fn install_hook_in(hooks_dir: &Path, name: &str, script: &str) {
+    fs::create_dir_all(hooks_dir).expect("Failed to create hooks directory");
+    let hook_path = hooks_dir.join(name);
+    fs::write(&hook_path, script).expect("Failed to write hook");
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))
+            .expect("Failed to make hook executable");
+    }
+}
+
 /// Clean up a test repository
 pub fn cleanup_test_repository(path: &Path) {
+    if let Ok(repo) = git2::Repository::open(path) {
+        crate::journal::remove_journal(&repo).expect("Failed to remove undo journal");
+    }
     if path.exists() {
         fs::remove_dir_all(path).expect("Failed to remove test directory");
     }
@@ -0,0 +1,87 @@
+use crate::blame::{self, BlameOptions};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+fn commit_file(test_dir: &Path, content: &str, author: &str, message: &str) -> String {
+    fs::write(test_dir.join("code.txt"), content).expect("Failed to write file");
+    test_utils::run_git_command(&["add", "code.txt"], &test_dir.to_path_buf());
+    let author = format!("{} <{}@example.com>", author, author.to_lowercase());
+    test_utils::run_git_command(&["commit", "-m", message, "--author", &author], &test_dir.to_path_buf());
+    let repo = Repository::open(test_dir).expect("Failed to open repository");
+    let id = repo.head().unwrap().target().unwrap();
+    id.to_string()
+}
+
+/// Expands hunks into the commit id for each line
+fn line_owners(hunks: &[blame::BlameHunk]) -> Vec<(usize, String)> {
+    hunks
+        .iter()
+        .flat_map(|hunk| (0..hunk.lines).map(move |i| (hunk.start_line + i, hunk.commit_id.clone())))
+        .collect()
+}
+
+#[test]
+fn test_blame_hunks_and_line_range() {
+    let test_dir = test_utils::create_test_repository("blame_hunks");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let first = commit_file(&test_dir, "one\ntwo\nthree\nfour\n", "Alice", "Add code");
+    let second = commit_file(&test_dir, "one\nTWO\nthree\nfour\nfive\n", "Bob", "Change code");
+
+    let hunks = blame::get_blame(repo_path.clone(), "code.txt".to_string(), None, None).expect("Failed to blame");
+    assert_eq!(
+        line_owners(&hunks),
+        vec![(1, first.clone()), (2, second.clone()), (3, first.clone()), (4, first.clone()), (5, second.clone())]
+    );
+    assert_eq!(hunks[0].author, "Alice");
+    assert_eq!(hunks[0].summary, "Add code");
+    assert_eq!(hunks[1].author, "Bob");
+    assert_eq!(hunks[1].author_email, "bob@example.com");
+    assert_eq!(hunks[0].orig_path, "code.txt");
+
+    // Older revisions and line ranges
+    let hunks = blame::get_blame(repo_path.clone(), "code.txt".to_string(), Some("HEAD~1".to_string()), None)
+        .expect("Failed to blame");
+    assert_eq!(line_owners(&hunks), (1..=4).map(|line| (line, first.clone())).collect::<Vec<_>>());
+
+    let options = BlameOptions { start_line: Some(2), end_line: Some(3), ..Default::default() };
+    let hunks = blame::get_blame(repo_path.clone(), "code.txt".to_string(), None, Some(options)).expect("Failed to blame");
+    assert_eq!(line_owners(&hunks), vec![(2, second.clone()), (3, first.clone())]);
+
+    assert!(blame::get_blame(repo_path.clone(), "missing.txt".to_string(), None, None).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_blame_ignore_whitespace_and_revs() {
+    let test_dir = test_utils::create_test_repository("blame_ignore");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let first = commit_file(&test_dir, "fn main() {\nprint();\n}\n", "Alice", "Add main");
+    let indent = commit_file(&test_dir, "fn main() {\n    print();\n}\n", "Bob", "Indent");
+    let rename = commit_file(&test_dir, "fn start() {\n    print();\n}\n", "Carol", "Rename main");
+    let reformat = commit_file(&test_dir, "fn start()  {\n    print();\n}\n", "Dave", "Reformat");
+
+    let owners = |options: BlameOptions| {
+        let hunks = blame::get_blame(repo_path.clone(), "code.txt".to_string(), None, Some(options)).expect("Failed to blame");
+        line_owners(&hunks).into_iter().map(|(_, id)| id).collect::<Vec<_>>()
+    };
+
+    assert_eq!(owners(BlameOptions::default()), vec![reformat.clone(), indent.clone(), first.clone()]);
+    let whitespace = BlameOptions { ignore_whitespace: true, ..Default::default() };
+    assert_eq!(owners(whitespace), vec![rename.clone(), first.clone(), first.clone()]);
+
+    // Blame passes over the ignored commits to the one before
+    fs::write(test_dir.join(".git-blame-ignore-revs"), format!("# Formatting\n{}\n{} # rename\n", reformat, rename))
+        .expect("Failed to write ignore file");
+    test_utils::run_git_command(&["config", "blame.ignoreRevsFile", ".git-blame-ignore-revs"], &test_dir);
+    assert_eq!(owners(BlameOptions::default()), vec![first.clone(), indent.clone(), first.clone()]);
+
+    let no_ignore = BlameOptions { no_ignore_revs: true, ..Default::default() };
+    assert_eq!(owners(no_ignore), vec![reformat.clone(), indent.clone(), first.clone()]);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -44,7 +44,7 @@ fn test_branch_operations() {
     let test_file = "branch_test_file.txt";
     fs::write(test_dir.join(test_file), "Branch test content").expect("Failed to write file");
     git::stage_file(repo_path.clone(), test_file.to_string()).expect("Failed to stage file");
-    git::create_commit(repo_path.clone(), "Commit on test branch".to_string())
+    git::create_commit(repo_path.clone(), "Commit on test branch".to_string(), None)
         .expect("Failed to create commit");
     
     // Checkout back to main
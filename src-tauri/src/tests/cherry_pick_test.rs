@@ -0,0 +1,188 @@
+use crate::git;
+use crate::reflog;
+use crate::repo_state;
+use crate::sequencer::{self, CherryPickOptions};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+fn head_id(repo_path: &str) -> String {
+    git::get_commits(repo_path.to_string(), None, Some(1)).expect("Failed to get commits")[0].id.clone()
+}
+
+fn branch_tip(test_dir: &std::path::Path, branch: &str) -> String {
+    let repo = Repository::open(test_dir).expect("Failed to open repository");
+    let id = repo.revparse_single(branch).expect("Failed to resolve branch").id().to_string();
+    id
+}
+
+#[test]
+fn test_cherry_pick_clean_and_no_commit() {
+    let test_dir = test_utils::create_multi_branch_repository("cherry_pick_clean");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let feature = branch_tip(&test_dir, "feature");
+    let original_head = head_id(&repo_path);
+    
+    // No-commit mode stages the change but leaves HEAD alone
+    let options = CherryPickOptions { no_commit: true, ..Default::default() };
+    let result = sequencer::cherry_pick(repo_path.clone(), vec![feature.clone()], Some(options))
+        .expect("Failed to cherry-pick");
+    assert!(result.success && result.created_commits.is_empty());
+    assert_eq!(head_id(&repo_path), original_head, "HEAD should not move in no-commit mode");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.len(), 1);
+    assert_eq!(status.staged[0].path, "feature.txt");
+    assert!(!test_dir.join(".git/sequencer").exists(), "Sequencer state should be cleaned up");
+    
+    test_utils::run_git_command(&["reset", "--hard", "HEAD"], &test_dir);
+    
+    // -x style annotation records where the commit came from
+    let options = CherryPickOptions { record_origin: true, ..Default::default() };
+    let result = sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string()], Some(options))
+        .expect("Failed to cherry-pick");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.created_commits.len(), 1);
+    
+    let commits = git::get_commits(repo_path.clone(), None, Some(2)).expect("Failed to get commits");
+    assert_eq!(commits[0].id, result.created_commits[0]);
+    assert_eq!(commits[0].summary, "Add feature");
+    assert_eq!(commits[0].parent_ids, vec![original_head]);
+    assert!(commits[0].message.ends_with(&format!("\n\n(cherry picked from commit {})\n", feature)),
+            "Unexpected message: {:?}", commits[0].message);
+    assert!(test_dir.join("feature.txt").exists());
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_cherry_pick_conflicts_continue_skip_abort() {
+    let test_dir = test_utils::create_multi_branch_repository("cherry_pick_conflicts");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let original_head = head_id(&repo_path);
+    let picks = vec!["conflict-branch".to_string(), "feature".to_string()];
+    
+    // The first pick conflicts and stops the sequence
+    let result = sequencer::cherry_pick(repo_path.clone(), picks.clone(), None).expect("Failed to cherry-pick");
+    assert!(result.has_conflicts);
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(result.current_commit, Some(branch_tip(&test_dir, "conflict-branch")));
+    assert_eq!(result.remaining, 2);
+    
+    // Starting another sequence is refused while one is in progress
+    assert!(sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string()], None).is_err());
+    
+    // Continuing requires the conflicts to be resolved
+    assert!(sequencer::cherry_pick_continue(repo_path.clone()).is_err());
+    
+    // Abort restores the starting point
+    sequencer::cherry_pick_abort(repo_path.clone()).expect("Failed to abort");
+    assert_eq!(head_id(&repo_path), original_head);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    assert!(!test_dir.join(".git/sequencer").exists());
+    
+    // Resolve and continue: the resolution is committed, then the rest is picked
+    sequencer::cherry_pick(repo_path.clone(), picks.clone(), None).expect("Failed to cherry-pick");
+    fs::write(test_dir.join("README.md"), "# Resolved").expect("Failed to resolve conflict");
+    git::stage_file(repo_path.clone(), "README.md".to_string()).expect("Failed to stage resolution");
+    let result = sequencer::cherry_pick_continue(repo_path.clone()).expect("Failed to continue");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.created_commits.len(), 2);
+    let summaries: Vec<String> = git::get_commits(repo_path.clone(), None, Some(2)).unwrap()
+        .into_iter().map(|c| c.summary).collect();
+    assert_eq!(summaries, vec!["Add feature", "Modify README in conflict branch"]);
+    
+    // Skip drops the conflicting commit and applies the rest
+    test_utils::run_git_command(&["reset", "--hard", &original_head], &test_dir);
+    sequencer::cherry_pick(repo_path.clone(), picks, None).expect("Failed to cherry-pick");
+    let result = sequencer::cherry_pick_skip(repo_path.clone()).expect("Failed to skip");
+    assert!(result.success);
+    assert_eq!(result.created_commits.len(), 1);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    assert!(test_dir.join("feature.txt").exists());
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_cherry_pick_merge_commit_needs_mainline() {
+    let test_dir = test_utils::create_multi_branch_repository("cherry_pick_mainline");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    // Build a merge commit on a side branch
+    test_utils::run_git_command(&["checkout", "-b", "side", "feature"], &test_dir);
+    fs::write(test_dir.join("side.txt"), "Side content").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "side.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Side change"], &test_dir);
+    test_utils::run_git_command(&["checkout", "feature"], &test_dir);
+    test_utils::run_git_command(&["merge", "--no-ff", "-m", "Merge side", "side"], &test_dir);
+    test_utils::run_git_command(&["checkout", "main"], &test_dir);
+    
+    let error = sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string()], None)
+        .expect_err("Picking a merge without mainline should fail");
+    assert!(error.to_string().contains("no mainline"), "{}", error);
+    assert!(!test_dir.join(".git/sequencer").exists(), "A rejected pick should leave no state behind");
+    
+    // Against the first parent, the merge brings in only the side branch change
+    let options = CherryPickOptions { mainline: Some(1), ..Default::default() };
+    let result = sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string()], Some(options))
+        .expect("Failed to cherry-pick merge");
+    assert!(result.success, "{}", result.message);
+    assert!(test_dir.join("side.txt").exists());
+    assert!(!test_dir.join("feature.txt").exists());
+    
+    // The mainline only applies to the merges of a batch; ordinary commits are picked as usual
+    test_utils::run_git_command(&["reset", "--hard", "HEAD~1"], &test_dir);
+    let options = CherryPickOptions { mainline: Some(1), ..Default::default() };
+    let result = sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string(), "feature~1".to_string()], Some(options))
+        .expect("Failed to cherry-pick batch");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.created_commits.len(), 2);
+    assert!(test_dir.join("side.txt").exists());
+    assert!(test_dir.join("feature.txt").exists());
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_cherry_pick_no_commit_sequence() {
+    let test_dir = test_utils::create_multi_branch_repository("cherry_pick_no_commit_sequence");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let original_head = head_id(&repo_path);
+
+    test_utils::run_git_command(&["checkout", "-b", "extra", "feature"], &test_dir);
+    fs::write(test_dir.join("extra.txt"), "Extra content").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "extra.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add extra"], &test_dir);
+    test_utils::run_git_command(&["checkout", "main"], &test_dir);
+
+    // Both picks build up in the index without any commits being written
+    let options = CherryPickOptions { no_commit: true, ..Default::default() };
+    let result = sequencer::cherry_pick(repo_path.clone(), vec!["feature".to_string(), "extra".to_string()], Some(options.clone()))
+        .expect("Failed to cherry-pick");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(head_id(&repo_path), original_head);
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    let mut staged: Vec<String> = status.staged.into_iter().map(|f| f.path).collect();
+    staged.sort();
+    assert_eq!(staged, vec!["extra.txt", "feature.txt"]);
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    assert!(reflog::dangling_commits(&repo, 10).unwrap().is_empty(), "No intermediate commits should be written");
+
+    // Skipping a conflicting step keeps what earlier steps staged
+    test_utils::run_git_command(&["reset", "--hard", &original_head], &test_dir);
+    let picks = vec!["feature".to_string(), "conflict-branch".to_string(), "extra".to_string()];
+    let result = sequencer::cherry_pick(repo_path.clone(), picks, Some(options)).expect("Failed to cherry-pick");
+    assert!(result.has_conflicts);
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(repo_state::get_repository_state(repo_path.clone()).unwrap().conflicted_files, vec!["README.md"]);
+    assert!(test_dir.join("feature.txt").exists());
+
+    let result = sequencer::cherry_pick_skip(repo_path.clone()).expect("Failed to skip");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(head_id(&repo_path), original_head);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    assert!(test_dir.join("feature.txt").exists() && test_dir.join("extra.txt").exists());
+    assert!(reflog::dangling_commits(&repo, 10).unwrap().is_empty());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
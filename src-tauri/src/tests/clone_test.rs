@@ -0,0 +1,107 @@
+use crate::clone::{self, CloneOptions, CloneStage};
+use crate::git;
+use crate::submodule;
+use crate::test_utils;
+use git2::Repository;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+
+fn clone_dest(name: &str) -> PathBuf {
+    let dest = std::env::temp_dir().join(format!("januslens_test_{}", name));
+    let _ = fs::remove_dir_all(&dest);
+    dest
+}
+
+#[test]
+fn test_init_repository() {
+    let path = clone_dest("init_repository");
+    let path_str = path.to_string_lossy().to_string();
+
+    let info = git::init_repository(path_str.clone(), None, Some("trunk".to_string())).expect("Failed to init");
+    assert_eq!(info.name, "januslens_test_init_repository");
+    let repo = Repository::open(&path).expect("Failed to open repository");
+    assert_eq!(repo.find_reference("HEAD").unwrap().symbolic_target(), Some("refs/heads/trunk"));
+    // Recent repositories are recorded in the test configuration, not the user's
+    let config_dir = git::get_config_dir().unwrap();
+    assert!(config_dir.starts_with(std::env::temp_dir()));
+    assert!(config_dir.join("recent_repos.json").exists());
+    assert!(git::list_repositories().unwrap().iter().any(|r| r.path == path_str));
+
+    assert!(git::init_repository(path_str.clone(), None, None).is_err(), "Existing repositories are refused");
+    assert!(git::init_repository("relative".to_string(), None, None).is_err());
+
+    let bare = clone_dest("init_repository_bare");
+    git::init_repository(bare.to_string_lossy().to_string(), Some(true), None).expect("Failed to init bare");
+    assert!(Repository::open(&bare).unwrap().is_bare());
+    let bad = clone_dest("init_repository_bad");
+    assert!(git::init_repository(bad.to_string_lossy().to_string(), None, Some("bad..name".to_string())).is_err());
+
+    test_utils::cleanup_test_repository(&path);
+    test_utils::cleanup_test_repository(&bare);
+}
+
+#[test]
+fn test_clone_local_with_progress() {
+    let source = test_utils::create_multi_branch_repository("clone_source");
+    let url = format!("file://{}", source.display());
+    let dest = clone_dest("clone_local");
+
+    let events = RefCell::new(Vec::new());
+    let options = CloneOptions { branch: Some("feature".to_string()), depth: Some(1), ..Default::default() };
+    let repo = clone::clone(&url, &dest, "clone-1", &options, &AtomicBool::new(false), &|progress| {
+        events.borrow_mut().push(progress.clone())
+    })
+    .expect("Failed to clone");
+
+    assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+    assert!(!repo.is_shallow(), "Depth does not apply to local clones");
+    assert!(dest.join("feature.txt").exists());
+    let events = events.into_inner();
+    assert!(events.iter().all(|event| event.operation_id == "clone-1"));
+    assert!(events.iter().any(|event| event.stage == CloneStage::Receiving || event.stage == CloneStage::Resolving));
+    assert!(events.iter().any(|event| event.stage == CloneStage::Checkout));
+
+    // Plain paths work too, and bare clones have no working tree
+    let bare = clone_dest("clone_bare");
+    let options = CloneOptions { bare: true, ..Default::default() };
+    let repo = clone::clone(&source.to_string_lossy(), &bare, "clone-2", &options, &AtomicBool::new(false), &|_| {})
+        .expect("Failed to clone bare");
+    assert!(repo.is_bare());
+
+    // Non-empty destinations are refused and left alone
+    assert!(clone::clone(&url, &dest, "clone-3", &CloneOptions::default(), &AtomicBool::new(false), &|_| {}).is_err());
+    assert!(dest.join("feature.txt").exists());
+
+    test_utils::cleanup_test_repository(&dest);
+    test_utils::cleanup_test_repository(&bare);
+    test_utils::cleanup_test_repository(&source);
+}
+
+#[test]
+fn test_clone_cancel_and_submodules() {
+    let sub_dir = test_utils::create_test_repository("clone_recursive_sub");
+    let super_dir = test_utils::create_test_repository("clone_recursive_super");
+    let sub_url = format!("file://{}", sub_dir.display());
+    test_utils::run_git_command(&["-c", "protocol.file.allow=always", "submodule", "add", &sub_url, "libs/sub"], &super_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add submodule"], &super_dir);
+    let url = format!("file://{}", super_dir.display());
+
+    // A cancelled clone leaves nothing behind
+    let dest = clone_dest("clone_cancelled");
+    let result = clone::clone(&url, &dest, "clone-4", &CloneOptions::default(), &AtomicBool::new(true), &|_| {});
+    assert!(result.is_err());
+    assert!(!dest.exists());
+
+    let dest = clone_dest("clone_recursive");
+    let options = CloneOptions { recurse_submodules: true, ..Default::default() };
+    clone::clone(&url, &dest, "clone-5", &options, &AtomicBool::new(false), &|_| {}).expect("Failed to clone");
+    assert!(dest.join("libs/sub/README.md").exists());
+    let submodules = submodule::list_submodules(dest.to_string_lossy().to_string()).unwrap();
+    assert!(submodules[0].checked_out && !submodules[0].out_of_date);
+
+    test_utils::cleanup_test_repository(&dest);
+    test_utils::cleanup_test_repository(&super_dir);
+    test_utils::cleanup_test_repository(&sub_dir);
+}
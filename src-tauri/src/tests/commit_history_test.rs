@@ -24,7 +24,7 @@ fn test_commit_history() {
         // Stage and commit
         git::stage_file(repo_path.clone(), file_name.clone()).expect("Failed to stage file");
         let commit_msg = format!("Commit {}", i);
-        let commit = git::create_commit(repo_path.clone(), commit_msg.clone())
+        let commit = git::create_commit(repo_path.clone(), commit_msg.clone(), None)
             .expect("Failed to create commit");
         
         // Verify commit details
@@ -69,7 +69,7 @@ fn test_commit_history() {
     fs::write(test_dir.join(branch_file), "Branch specific content")
         .expect("Failed to write file");
     git::stage_file(repo_path.clone(), branch_file.to_string()).expect("Failed to stage file");
-    git::create_commit(repo_path.clone(), "Branch specific commit".to_string())
+    git::create_commit(repo_path.clone(), "Branch specific commit".to_string(), None)
         .expect("Failed to create commit");
     
     // Get commits for this specific branch
@@ -0,0 +1,78 @@
+use crate::error::JanusError;
+use crate::git::{self, AuthorInfo, CommitOptions};
+use crate::test_utils;
+use std::fs;
+
+#[test]
+fn test_commit_trailers_and_allow_empty() {
+    let test_dir = test_utils::create_test_repository("commit_trailers");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    // Known authors come from history
+    let authors = git::get_known_authors(repo_path.clone(), None).expect("Failed to get authors");
+    assert_eq!(authors, vec![AuthorInfo {
+        name: "Test User".to_string(),
+        email: "test@example.com".to_string(),
+    }]);
+    
+    // An empty commit is rejected unless explicitly allowed
+    assert!(git::create_commit(repo_path.clone(), "Empty".to_string(), None).is_err(),
+            "Empty commit should fail without allow_empty");
+    
+    let options = CommitOptions {
+        signoff: true,
+        co_authors: vec![AuthorInfo {
+            name: "Pair Programmer".to_string(),
+            email: "pair@example.com".to_string(),
+        }],
+        allow_empty: true,
+        ..Default::default()
+    };
+    let commit = git::create_commit(repo_path.clone(), "Empty with trailers".to_string(), Some(options))
+        .expect("Failed to create empty commit");
+    
+    assert_eq!(commit.summary, "Empty with trailers");
+    assert!(commit.message.contains("\n\nSigned-off-by: Test User <test@example.com>\n"),
+            "Message should contain a sign-off trailer: {}", commit.message);
+    assert!(commit.message.contains("Co-authored-by: Pair Programmer <pair@example.com>"),
+            "Message should contain a co-author trailer: {}", commit.message);
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_commit_hooks() {
+    let test_dir = test_utils::create_test_repository("commit_hooks");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    fs::write(test_dir.join("hooked.txt"), "Hooked content").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "hooked.txt".to_string()).expect("Failed to stage file");
+    
+    // A failing pre-commit hook aborts the commit with its output
+    test_utils::install_hook(&test_dir, "pre-commit", "#!/bin/sh\necho 'lint failed'\nexit 3\n");
+    match git::create_commit(repo_path.clone(), "Blocked".to_string(), None) {
+        Err(JanusError::HookFailed { hook, exit_code, stdout, .. }) => {
+            assert_eq!(hook, "pre-commit");
+            assert_eq!(exit_code, Some(3));
+            assert_eq!(stdout.trim(), "lint failed");
+        }
+        other => panic!("Expected pre-commit failure, got {:?}", other),
+    }
+    
+    // no_verify skips the hook entirely
+    let options = CommitOptions { no_verify: true, ..Default::default() };
+    let commit = git::create_commit(repo_path.clone(), "Unverified".to_string(), Some(options))
+        .expect("Commit with no_verify should succeed");
+    assert_eq!(commit.summary, "Unverified");
+    
+    // The commit-msg hook can rewrite the message
+    fs::remove_file(test_dir.join(".git/hooks/pre-commit")).expect("Failed to remove hook");
+    test_utils::install_hook(&test_dir, "commit-msg", "#!/bin/sh\necho 'Ticket: JL-1' >> \"$1\"\n");
+    let commit = git::create_commit(repo_path.clone(), "Rewritten".to_string(),
+                                    Some(CommitOptions { allow_empty: true, ..Default::default() }))
+        .expect("Commit with commit-msg hook should succeed");
+    assert!(commit.message.contains("Ticket: JL-1"), "Hook should amend message: {}", commit.message);
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,122 @@
+use crate::conflicts::{self, ConflictResolution};
+use crate::git;
+use crate::test_utils;
+use std::fs;
+
+#[test]
+fn test_parse_conflict_regions() {
+    let content = "intro\n<<<<<<< HEAD\nours 1\nours 2\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> topic\noutro\n<<<<<<< HEAD\na\n=======\nb\n>>>>>>> topic\n";
+    let regions = conflicts::parse_conflict_regions(content);
+
+    assert_eq!(regions.len(), 2);
+    assert_eq!((regions[0].start_line, regions[0].end_line), (2, 9));
+    assert_eq!(regions[0].ours_label, "HEAD");
+    assert_eq!(regions[0].theirs_label, "topic");
+    assert_eq!(regions[0].ours, "ours 1\nours 2\n");
+    assert_eq!(regions[0].base.as_deref(), Some("base\n"));
+    assert_eq!(regions[0].theirs, "theirs\n");
+    assert_eq!((regions[1].start_line, regions[1].end_line), (11, 15));
+    assert_eq!(regions[1].base, None);
+
+    assert!(conflicts::parse_conflict_regions("no markers\n========\n").is_empty());
+}
+
+#[test]
+fn test_conflict_content_and_resolutions() {
+    let test_dir = test_utils::create_multi_branch_repository("conflict_content");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let readme = test_dir.join("README.md");
+
+    let merge = |expected_conflicts: bool| {
+        let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+        assert_eq!(result.has_conflicts, expected_conflicts);
+    };
+    merge(true);
+
+    let conflict = conflicts::get_conflict(repo_path.clone(), "README.md".to_string()).expect("Failed to get conflict");
+    assert_eq!(conflict.base.unwrap().content.as_deref(), Some("# Test Repository"));
+    assert_eq!(conflict.ours.unwrap().content.as_deref(), Some("# Modified in main branch"));
+    assert_eq!(conflict.theirs.unwrap().content.as_deref(), Some("# Modified in conflict branch"));
+    assert_eq!(conflict.regions.len(), 1);
+    assert_eq!(conflict.regions[0].ours, "# Modified in main branch\n");
+    assert_eq!(conflict.regions[0].theirs, "# Modified in conflict branch\n");
+    assert!(conflicts::get_conflict(repo_path.clone(), "feature.txt".to_string()).is_err());
+
+    // Taking theirs stages their version and clears the conflict
+    conflicts::resolve_conflict(repo_path.clone(), "README.md".to_string(), ConflictResolution::Theirs)
+        .expect("Failed to resolve");
+    assert_eq!(fs::read_to_string(&readme).unwrap(), "# Modified in conflict branch");
+    assert!(git::get_merge_state(repo_path.clone()).unwrap().conflicted_files.is_empty());
+
+    git::merge_abort(repo_path.clone()).expect("Failed to abort");
+    merge(true);
+    conflicts::resolve_conflict(repo_path.clone(), "README.md".to_string(), ConflictResolution::Both)
+        .expect("Failed to resolve");
+    assert_eq!(fs::read_to_string(&readme).unwrap(), "# Modified in main branch\n# Modified in conflict branch\n");
+
+    git::merge_abort(repo_path.clone()).expect("Failed to abort");
+    merge(true);
+    conflicts::resolve_conflict(
+        repo_path.clone(),
+        "README.md".to_string(),
+        ConflictResolution::Content { content: "# Merged by hand\n".to_string() },
+    )
+    .expect("Failed to resolve");
+    let result = git::merge_continue(repo_path.clone(), None).expect("Failed to continue merge");
+    assert!(result.success);
+    assert_eq!(fs::read_to_string(&readme).unwrap(), "# Merged by hand\n");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty() && status.unstaged.is_empty());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolution_keeps_the_side_file_mode() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let test_dir = test_utils::create_test_repository("conflict_modes");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let script = test_dir.join("run.sh");
+    let link = test_dir.join("current");
+
+    fs::write(&script, "echo base\n").expect("Failed to write file");
+    symlink("base.txt", &link).expect("Failed to create symlink");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add script and link"], &test_dir);
+
+    // The other branch makes the script executable and repoints the link
+    test_utils::run_git_command(&["checkout", "-b", "topic"], &test_dir);
+    fs::write(&script, "echo topic\n").expect("Failed to write file");
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).expect("Failed to chmod");
+    fs::remove_file(&link).expect("Failed to remove link");
+    symlink("topic.txt", &link).expect("Failed to create symlink");
+    test_utils::run_git_command(&["commit", "-am", "Topic changes"], &test_dir);
+    test_utils::run_git_command(&["checkout", "-"], &test_dir);
+    fs::write(&script, "echo main\n").expect("Failed to write file");
+    fs::remove_file(&link).expect("Failed to remove link");
+    symlink("main.txt", &link).expect("Failed to create symlink");
+    test_utils::run_git_command(&["commit", "-am", "Main changes"], &test_dir);
+
+    let result = git::merge_branch(repo_path.clone(), "topic".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+    for path in ["run.sh", "current"] {
+        conflicts::resolve_conflict(repo_path.clone(), path.to_string(), ConflictResolution::Theirs)
+            .expect("Failed to resolve");
+    }
+
+    assert_eq!(fs::read_to_string(&script).unwrap(), "echo topic\n");
+    assert_ne!(fs::metadata(&script).unwrap().permissions().mode() & 0o111, 0, "Script should be executable");
+    assert_eq!(fs::read_link(&link).unwrap(), std::path::PathBuf::from("topic.txt"));
+
+    let repo = git2::Repository::open(&test_dir).expect("Failed to open repository");
+    let index = repo.index().expect("Failed to read index");
+    assert!(!index.has_conflicts());
+    assert_eq!(index.get_path(std::path::Path::new("run.sh"), 0).unwrap().mode, 0o100755);
+    assert_eq!(index.get_path(std::path::Path::new("current"), 0).unwrap().mode, 0o120000);
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.unstaged.is_empty(), "Working tree should match what was staged");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -9,7 +9,7 @@ fn test_file_listing_and_diff() {
     let repo_path = test_dir.to_string_lossy().to_string();
     
     // Get initial file listing (should just be README.md)
-    let initial_files = git::list_files(repo_path.clone(), None)
+    let initial_files = git::list_files(repo_path.clone(), None, None)
         .expect("Failed to list files");
     assert!(initial_files.len() >= 1, "Should have at least one file initially");
     
@@ -29,7 +29,7 @@ fn test_file_listing_and_diff() {
     fs::write(dir_path.join("file2.txt"), "File 2 content").expect("Failed to write file2");
     
     // List files again to see the directory
-    let files_with_dir = git::list_files(repo_path.clone(), None)
+    let files_with_dir = git::list_files(repo_path.clone(), None, None)
         .expect("Failed to list files");
     let initial_count = initial_files.len();
     assert_eq!(files_with_dir.len(), initial_count + 1, 
@@ -42,7 +42,7 @@ fn test_file_listing_and_diff() {
     assert_eq!(dir_entry.type_, "directory", "Entry should be a directory");
     
     // List files in the directory
-    let dir_files = git::list_files(repo_path.clone(), Some(dir_name.to_string()))
+    let dir_files = git::list_files(repo_path.clone(), Some(dir_name.to_string()), None)
         .expect("Failed to list files in directory");
     assert_eq!(dir_files.len(), 2, "Directory should contain 2 files");
     assert!(dir_files.iter().any(|f| f.name == "file1.txt"), "file1.txt should be in directory");
@@ -56,7 +56,7 @@ fn test_file_listing_and_diff() {
     // Create and commit the file
     fs::write(test_dir.join(test_file), initial_content).expect("Failed to write test file");
     git::stage_file(repo_path.clone(), test_file.to_string()).expect("Failed to stage file");
-    git::create_commit(repo_path.clone(), "Add file for diff testing".to_string())
+    git::create_commit(repo_path.clone(), "Add file for diff testing".to_string(), None)
         .expect("Failed to create commit");
     
     // Modify the file
@@ -0,0 +1,66 @@
+use crate::history;
+use crate::test_utils;
+use std::fs;
+
+#[test]
+fn test_file_history_follows_renames() {
+    let test_dir = test_utils::create_test_repository("file_history");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let content = "line one\nline two\nline three\nline four\nline five\n";
+
+    fs::write(test_dir.join("old.txt"), content).expect("Failed to write file");
+    test_utils::run_git_command(&["add", "old.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add old"], &test_dir);
+
+    fs::write(test_dir.join("old.txt"), format!("{}line six\n", content)).expect("Failed to write file");
+    test_utils::run_git_command(&["commit", "-am", "Extend old"], &test_dir);
+
+    fs::write(test_dir.join("README.md"), "# Unrelated").expect("Failed to write file");
+    test_utils::run_git_command(&["commit", "-am", "Touch README"], &test_dir);
+
+    test_utils::run_git_command(&["mv", "old.txt", "new.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Rename to new"], &test_dir);
+
+    fs::write(test_dir.join("new.txt"), format!("{}line six\nline seven\n", content)).expect("Failed to write file");
+    test_utils::run_git_command(&["commit", "-am", "Extend new"], &test_dir);
+
+    let page = history::get_file_history(repo_path.clone(), "new.txt".to_string(), None, None, None)
+        .expect("Failed to get file history");
+    assert!(!page.has_more);
+    let summary: Vec<(&str, &str, &str)> = page
+        .entries
+        .iter()
+        .map(|e| (e.commit.summary.as_str(), e.path.as_str(), e.change.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Extend new", "new.txt", "modified"),
+            ("Rename to new", "new.txt", "renamed"),
+            ("Extend old", "old.txt", "modified"),
+            ("Add old", "old.txt", "new"),
+        ]
+    );
+    assert_eq!(page.entries[1].old_path.as_deref(), Some("old.txt"));
+
+    // Pages pick up where the previous one stopped, across the rename
+    let page = history::get_file_history(repo_path.clone(), "new.txt".to_string(), None, Some(1), Some(2))
+        .expect("Failed to get file history");
+    assert!(page.has_more);
+    let summaries: Vec<&str> = page.entries.iter().map(|e| e.commit.summary.as_str()).collect();
+    assert_eq!(summaries, vec!["Rename to new", "Extend old"]);
+
+    // Deleted files still have a history
+    test_utils::run_git_command(&["rm", "-q", "new.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Remove new"], &test_dir);
+    let page = history::get_file_history(repo_path.clone(), "new.txt".to_string(), None, None, Some(1))
+        .expect("Failed to get file history");
+    assert_eq!(page.entries[0].change, "deleted");
+    assert!(page.has_more);
+
+    let page = history::get_file_history(repo_path.clone(), "README.md".to_string(), Some("HEAD~2".to_string()), None, None)
+        .expect("Failed to get file history");
+    assert_eq!(page.entries.len(), 2);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -54,7 +54,7 @@ fn test_commit_creation() {
     
     // Create a commit
     let commit_message = "Test commit message";
-    let commit = git::create_commit(repo_path.clone(), commit_message.to_string())
+    let commit = git::create_commit(repo_path.clone(), commit_message.to_string(), None)
         .expect("Failed to create commit");
     
     // Verify commit details
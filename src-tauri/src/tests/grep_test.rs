@@ -0,0 +1,103 @@
+use crate::git;
+use crate::grep::{self, GrepOptions, MatchRange};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+#[test]
+fn test_grep_worktree_and_revision() {
+    let test_dir = test_utils::create_test_repository("grep_worktree");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::create_dir_all(test_dir.join("src")).expect("Failed to create directory");
+    fs::write(test_dir.join("src/main.rs"), "fn main() {\n    let todo = 1; // TODO: remove todo\n}\n").expect("Failed to write file");
+    fs::write(test_dir.join("src/lib.rs"), "// TODO later\n").expect("Failed to write file");
+    fs::write(test_dir.join("data.bin"), b"TODO\0binary").expect("Failed to write file");
+    fs::write(test_dir.join(".gitignore"), "*.log\n").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add sources"], &test_dir);
+
+    fs::write(test_dir.join("src/lib.rs"), "// done\n").expect("Failed to write file");
+    fs::write(test_dir.join("notes.txt"), "TODO: untracked\n").expect("Failed to write file");
+    fs::write(test_dir.join("debug.log"), "TODO: ignored\n").expect("Failed to write file");
+
+    // The working tree copy of tracked files is searched; binary, untracked and ignored files are not
+    let result = grep::grep_worktree(repo_path.clone(), "TODO".to_string(), None).expect("Failed to grep");
+    assert_eq!(result.total_matches, 1);
+    assert!(!result.truncated);
+    assert_eq!(result.files.len(), 1);
+    let file = &result.files[0];
+    assert_eq!(file.path, "src/main.rs");
+    assert_eq!(file.lines[0].line_number, 2);
+    assert_eq!(file.lines[0].ranges, vec![MatchRange { start: 21, end: 25 }]);
+
+    let options = GrepOptions { include_untracked: true, ignore_case: true, ..Default::default() };
+    let result = grep::grep_worktree(repo_path.clone(), "todo".to_string(), Some(options)).expect("Failed to grep");
+    let paths: Vec<&str> = result.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["notes.txt", "src/main.rs"]);
+    assert_eq!(result.files[1].lines[0].ranges.len(), 3);
+
+    // At a revision the committed content counts
+    let options = GrepOptions { revspec: Some("HEAD".to_string()), paths: vec!["src".to_string()], ..Default::default() };
+    let result = grep::grep_worktree(repo_path.clone(), "TODO".to_string(), Some(options)).expect("Failed to grep");
+    let paths: Vec<&str> = result.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/lib.rs", "src/main.rs"]);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_grep_options_and_caps() {
+    let test_dir = test_utils::create_test_repository("grep_options");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+
+    fs::write(test_dir.join("a.txt"), "a.b\naxb\na.b\n").expect("Failed to write file");
+    fs::write(test_dir.join("big.txt"), format!("a.b\n{}", "x".repeat(4096))).expect("Failed to write file");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add files"], &test_dir);
+
+    let options = GrepOptions { fixed_strings: true, paths: vec!["a.txt".to_string()], ..Default::default() };
+    let result = grep::grep(&repo, "a.b", &options).expect("Failed to grep");
+    assert_eq!(result.total_matches, 2);
+
+    let options = GrepOptions { paths: vec!["a.txt".to_string()], ..Default::default() };
+    assert_eq!(grep::grep(&repo, "a.b", &options).expect("Failed to grep").total_matches, 3);
+
+    let options = GrepOptions { max_results: Some(2), paths: vec!["a.txt".to_string()], ..Default::default() };
+    let result = grep::grep(&repo, "a.b", &options).expect("Failed to grep");
+    assert_eq!(result.total_matches, 2);
+    assert!(result.truncated);
+
+    // Using up the budget exactly is not truncation, even with files left to search
+    let options = GrepOptions { max_results: Some(1), ..Default::default() };
+    let result = grep::grep(&repo, "axb", &options).expect("Failed to grep");
+    assert_eq!(result.total_matches, 1);
+    assert!(!result.truncated);
+
+    let options = GrepOptions { max_file_size: Some(1024), fixed_strings: true, ..Default::default() };
+    let result = grep::grep(&repo, "a.b", &options).expect("Failed to grep");
+    assert!(result.files.iter().all(|f| f.path != "big.txt"));
+
+    assert!(grep::grep(&repo, "(", &GrepOptions::default()).is_err());
+    let options = GrepOptions { paths: vec!["../outside".to_string()], ..Default::default() };
+    assert!(grep::grep(&repo, "a", &options).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_grep_conflicted_file_once() {
+    let test_dir = test_utils::create_multi_branch_repository("grep_conflicted");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+
+    // The index holds three stages of README.md, but there is one file to search
+    let result = grep::grep_worktree(repo_path.clone(), "Modified".to_string(), None).expect("Failed to grep");
+    let paths: Vec<&str> = result.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["README.md"]);
+    assert_eq!(result.total_matches, 2, "Both sides of the conflict markers match once");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
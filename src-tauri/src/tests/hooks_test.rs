@@ -0,0 +1,88 @@
+#![cfg(unix)]
+
+use crate::git;
+use crate::hooks::{self, PushUpdate};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::time::Duration;
+
+#[test]
+fn test_hooks_path_args_and_stdin() {
+    let test_dir = test_utils::create_test_repository("hooks_runner");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    
+    // Missing hooks are not an error
+    assert!(hooks::run_hook(&repo, "pre-push", &[], None).expect("Hook lookup failed").is_none());
+    
+    // core.hooksPath is resolved relative to the working tree
+    test_utils::run_git_command(&["config", "core.hooksPath", "custom-hooks"], &test_dir);
+    assert_eq!(hooks::hooks_dir(&repo), test_dir.join("custom-hooks"));
+    
+    test_utils::install_hook_in(&test_dir.join("custom-hooks"), "pre-push",
+        "#!/bin/sh\necho \"remote=$1 url=$2\"\ncat\necho 'rejected' >&2\nexit 1\n");
+    
+    let updates = vec![PushUpdate {
+        local_ref: "refs/heads/main".to_string(),
+        local_oid: "a".repeat(40),
+        remote_ref: "refs/heads/main".to_string(),
+        remote_oid: "0".repeat(40),
+    }];
+    let error = hooks::pre_push(&repo, "origin", "file:///tmp/origin.git", &updates)
+        .expect_err("Failing pre-push hook should abort");
+    
+    match error {
+        crate::error::JanusError::HookFailed { hook, exit_code, stdout, stderr } => {
+            assert_eq!(hook, "pre-push");
+            assert_eq!(exit_code, Some(1));
+            assert!(stdout.starts_with("remote=origin url=file:///tmp/origin.git\n"));
+            assert!(stdout.contains(&format!("refs/heads/main {} refs/heads/main {}", "a".repeat(40), "0".repeat(40))));
+            assert_eq!(stderr.trim(), "rejected");
+        }
+        other => panic!("Expected HookFailed, got {:?}", other),
+    }
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_hook_timeout_and_notifications() {
+    let test_dir = test_utils::create_multi_branch_repository("hooks_timeout");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    
+    // A hook that outlives its timeout is killed and reported
+    test_utils::install_hook(&test_dir, "pre-commit", "#!/bin/sh\nsleep 5\n");
+    let result = hooks::run_hook_with_timeout(&repo, "pre-commit", &[], None, Duration::from_millis(200))
+        .expect("Hook should run")
+        .expect("Hook should be installed");
+    assert!(result.timed_out, "Hook should time out");
+    assert!(!result.success());
+    fs::remove_file(test_dir.join(".git/hooks/pre-commit")).expect("Failed to remove hook");
+    
+    // A background process holding the pipes open does not keep a finished hook waiting
+    test_utils::install_hook(&test_dir, "pre-commit", "#!/bin/sh\necho done\nsleep 5 &\n");
+    let started = std::time::Instant::now();
+    let result = hooks::run_hook_with_timeout(&repo, "pre-commit", &[], None, Duration::from_millis(500))
+        .expect("Hook should run")
+        .expect("Hook should be installed");
+    assert!(started.elapsed() < Duration::from_secs(3), "Took {:?}", started.elapsed());
+    assert!(result.success() && !result.timed_out);
+    assert_eq!(result.stdout.trim(), "done");
+    fs::remove_file(test_dir.join(".git/hooks/pre-commit")).expect("Failed to remove hook");
+    
+    // post-checkout receives the old and new HEAD plus the branch flag
+    let old_head = repo.head().unwrap().target().unwrap();
+    let feature_head = repo.revparse_single("feature").unwrap().id();
+    test_utils::install_hook(&test_dir, "post-checkout", "#!/bin/sh\necho \"$1 $2 $3\" > checkout.log\n");
+    git::checkout_branch(repo_path.clone(), "feature".to_string()).expect("Failed to checkout feature");
+    let logged = fs::read_to_string(test_dir.join("checkout.log")).expect("post-checkout should run");
+    assert_eq!(logged.trim(), format!("{} {} 1", old_head, feature_head));
+    fs::remove_file(test_dir.join("checkout.log")).expect("Failed to remove log");
+    
+    // A failing post-checkout hook cannot undo the checkout
+    test_utils::install_hook(&test_dir, "post-checkout", "#!/bin/sh\nexit 1\n");
+    git::checkout_branch(repo_path.clone(), "main".to_string()).expect("Checkout should ignore post-checkout failures");
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
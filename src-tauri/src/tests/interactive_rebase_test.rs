@@ -0,0 +1,165 @@
+use crate::git;
+use crate::interactive_rebase::{self, RebaseAction, RebasePlanStep, RebaseStop, RewordPrompt};
+use crate::sequencer;
+use crate::test_utils;
+use git2::Repository;
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+
+fn commit_file(test_dir: &Path, file: &str, content: &str, message: &str) -> String {
+    let repo_path = test_dir.to_string_lossy().to_string();
+    fs::write(test_dir.join(file), content).expect("Failed to write file");
+    git::stage_file(repo_path.clone(), file.to_string()).expect("Failed to stage file");
+    git::create_commit(repo_path, message.to_string(), None).expect("Failed to create commit").id
+}
+
+fn step(action: RebaseAction, commit_id: &str) -> RebasePlanStep {
+    RebasePlanStep { action, commit_id: commit_id.to_string(), message: None }
+}
+
+fn summaries(repo_path: &str, count: u32) -> Vec<String> {
+    git::get_commits(repo_path.to_string(), None, Some(count)).unwrap()
+        .into_iter().map(|c| c.summary).collect()
+}
+
+fn no_prompt(_: &RewordPrompt) {}
+
+#[test]
+fn test_rebase_todo_with_autosquash() {
+    let test_dir = test_utils::create_test_repository("interactive_rebase_autosquash");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    commit_file(&test_dir, "a.txt", "A", "Add a");
+    commit_file(&test_dir, "b.txt", "B", "Add b");
+    commit_file(&test_dir, "a.txt", "A fixed", "fixup! Add a");
+
+    let plan = interactive_rebase::get_rebase_todo(repo_path.clone(), "HEAD~3".to_string(), Some(false))
+        .expect("Failed to plan rebase");
+    let listed: Vec<(RebaseAction, &str)> = plan.iter().map(|e| (e.action, e.commit.summary.as_str())).collect();
+    assert_eq!(listed, vec![
+        (RebaseAction::Pick, "Add a"),
+        (RebaseAction::Pick, "Add b"),
+        (RebaseAction::Pick, "fixup! Add a"),
+    ]);
+
+    // Autosquash moves the fixup under its target
+    let plan = interactive_rebase::get_rebase_todo(repo_path.clone(), "HEAD~3".to_string(), Some(true))
+        .expect("Failed to plan rebase");
+    let listed: Vec<(RebaseAction, &str)> = plan.iter().map(|e| (e.action, e.commit.summary.as_str())).collect();
+    assert_eq!(listed, vec![
+        (RebaseAction::Pick, "Add a"),
+        (RebaseAction::Fixup, "fixup! Add a"),
+        (RebaseAction::Pick, "Add b"),
+    ]);
+
+    let steps = plan.iter().map(|e| step(e.action, &e.commit.id)).collect();
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let result = interactive_rebase::start_plan(&repo, "HEAD~3", steps, &no_prompt).expect("Failed to rebase");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(summaries(&repo_path, 3), vec!["Add b", "Add a", "Initial commit"]);
+    assert_eq!(fs::read_to_string(test_dir.join("a.txt")).unwrap(), "A fixed");
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"), "HEAD should be back on the branch");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_interactive_rebase_reword_squash_drop() {
+    let test_dir = test_utils::create_test_repository("interactive_rebase_reword");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let a = commit_file(&test_dir, "a.txt", "A", "Add a");
+    let b = commit_file(&test_dir, "b.txt", "B", "Add b");
+    let c = commit_file(&test_dir, "c.txt", "C", "Add c");
+
+    let prompts = RefCell::new(Vec::new());
+    let on_reword = |prompt: &RewordPrompt| prompts.borrow_mut().push(prompt.clone());
+    let plan = vec![
+        step(RebaseAction::Reword, &a),
+        step(RebaseAction::Squash, &b),
+        step(RebaseAction::Drop, &c),
+    ];
+
+    // The reword pauses and asks for a message through the event callback
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let result = interactive_rebase::start_plan(&repo, "HEAD~3", plan, &on_reword).expect("Failed to rebase");
+    assert_eq!(result.stopped, Some(RebaseStop::Reword));
+    assert_eq!(prompts.borrow().len(), 1);
+    assert_eq!(prompts.borrow()[0].message, "Add a\n");
+
+    let status = interactive_rebase::get_interactive_rebase_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.in_progress);
+    assert_eq!(status.head_name.as_deref(), Some("main"));
+    assert_eq!(status.remaining.len(), 2);
+
+    let result = interactive_rebase::continue_plan(&repo, Some("Renamed a".to_string()), &on_reword)
+        .expect("Failed to continue");
+    assert!(result.success, "{}", result.message);
+
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    assert_eq!(head.message, "Renamed a\n\nAdd b\n", "Squash should combine the messages");
+    assert_eq!(summaries(&repo_path, 2), vec!["Renamed a", "Initial commit"]);
+    assert!(test_dir.join("b.txt").exists());
+    assert!(!test_dir.join("c.txt").exists(), "Dropped commit should be gone");
+    assert!(!interactive_rebase::get_interactive_rebase_status(repo_path.clone()).unwrap().in_progress);
+
+    // A squash with nothing before it is rejected
+    let error = interactive_rebase::start_plan(&repo, "HEAD~1", vec![step(RebaseAction::Squash, &head.id)], &no_prompt);
+    assert!(error.is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_interactive_rebase_conflict_abort_and_edit() {
+    let test_dir = test_utils::create_test_repository("interactive_rebase_edit");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let a = commit_file(&test_dir, "README.md", "one", "First edit");
+    let b = commit_file(&test_dir, "README.md", "two", "Second edit");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+
+    // Reordering dependent edits conflicts
+    let reordered = vec![step(RebaseAction::Pick, &b), step(RebaseAction::Pick, &a)];
+    let result = interactive_rebase::start_plan(&repo, "HEAD~2", reordered, &no_prompt).expect("Failed to rebase");
+    assert_eq!(result.stopped, Some(RebaseStop::Conflict));
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(result.current_commit, Some(b.clone()));
+
+    // Other operations are refused while the plan is paused
+    assert!(sequencer::cherry_pick(repo_path.clone(), vec![a.clone()], None).is_err());
+    assert!(interactive_rebase::continue_plan(&repo, None, &no_prompt).is_err());
+
+    interactive_rebase::abort_plan(&repo).expect("Failed to abort");
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+    assert_eq!(repo.head().unwrap().target().unwrap().to_string(), b);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "two");
+
+    // Edit stops after the commit so it can be amended
+    let plan = vec![step(RebaseAction::Edit, &a), step(RebaseAction::Pick, &b)];
+    let result = interactive_rebase::start_plan(&repo, "HEAD~2", plan, &no_prompt).expect("Failed to rebase");
+    assert_eq!(result.stopped, Some(RebaseStop::Edit));
+    assert_eq!(result.head, a, "Unchanged commits are reused");
+
+    fs::write(test_dir.join("extra.txt"), "Extra").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "extra.txt".to_string()).expect("Failed to stage file");
+
+    // HEAD is detached and git sees no rebase, so commands that move HEAD check for the plan
+    assert!(git::create_commit(repo_path.clone(), "Extra".to_string(), None).is_err());
+    assert!(git::checkout_branch(repo_path.clone(), "main".to_string()).is_err());
+    assert!(git::merge_branch(repo_path.clone(), "main".to_string(), None).is_err());
+    assert!(git::reset_to(repo_path.clone(), "HEAD~1".to_string(), git::ResetMode::Hard).is_err());
+    assert_eq!(repo.head().unwrap().target().unwrap().to_string(), a);
+    assert!(!repo.head().unwrap().is_branch());
+
+    let result = interactive_rebase::continue_plan(&repo, None, &no_prompt).expect("Failed to continue");
+    assert!(result.success, "{}", result.message);
+
+    assert_eq!(summaries(&repo_path, 3), vec!["Second edit", "First edit", "Initial commit"]);
+    let amended = repo.revparse_single("HEAD~1").unwrap().peel_to_commit().unwrap();
+    assert!(amended.tree().unwrap().get_name("extra.txt").is_some(), "Staged changes should be amended in");
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "two");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,53 @@
+use crate::git::{self, FileEntry, ListFilesOptions};
+use crate::test_utils;
+use std::fs;
+
+fn find<'a>(entries: &'a [FileEntry], name: &str) -> &'a FileEntry {
+    entries.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("{} should be listed", name))
+}
+
+#[test]
+fn test_list_files_status_markers() {
+    let test_dir = test_utils::create_test_repository("list_files_status");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::create_dir_all(test_dir.join("src/deep")).expect("Failed to create directories");
+    fs::create_dir_all(test_dir.join("docs")).expect("Failed to create directories");
+    fs::create_dir_all(test_dir.join("build")).expect("Failed to create directories");
+    fs::write(test_dir.join("src/deep/lib.rs"), "pub fn lib() {}\n").expect("Failed to write file");
+    fs::write(test_dir.join("docs/guide.md"), "# Guide\n").expect("Failed to write file");
+    fs::write(test_dir.join(".gitignore"), "build/\n*.log\n").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add sources"], &test_dir);
+
+    fs::write(test_dir.join("src/deep/lib.rs"), "pub fn lib() { changed() }\n").expect("Failed to write file");
+    fs::write(test_dir.join("notes.txt"), "Untracked").expect("Failed to write file");
+    fs::write(test_dir.join("debug.log"), "Ignored").expect("Failed to write file");
+    fs::write(test_dir.join("build/out.bin"), "Ignored").expect("Failed to write file");
+
+    let entries = git::list_files(repo_path.clone(), None, None).expect("Failed to list files");
+    assert_eq!(find(&entries, ".git").status, "ignored");
+    assert_eq!(find(&entries, "README.md").status, "tracked");
+    assert!(!find(&entries, "README.md").has_changes);
+    assert_eq!(find(&entries, "notes.txt").status, "untracked");
+    assert_eq!(find(&entries, "debug.log").status, "ignored");
+    assert_eq!(find(&entries, "build").status, "ignored");
+
+    // Directory flags look at everything below them
+    let src = find(&entries, "src");
+    assert_eq!((src.status.as_str(), src.has_changes), ("tracked", true));
+    let docs = find(&entries, "docs");
+    assert_eq!((docs.status.as_str(), docs.has_changes), ("tracked", false));
+
+    let entries = git::list_files(repo_path.clone(), Some("src/deep".to_string()), None).expect("Failed to list files");
+    assert_eq!(find(&entries, "lib.rs").status, "modified");
+    assert_eq!(find(&entries, "lib.rs").path, "src/deep/lib.rs");
+
+    // Hidden by option
+    let options = ListFilesOptions { hide_ignored: true, hide_git_dir: true };
+    let entries = git::list_files(repo_path.clone(), None, Some(options)).expect("Failed to list files");
+    let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["docs", "src", ".gitignore", "README.md", "notes.txt"]);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,113 @@
+use crate::git::{self, MergeOutcome};
+use crate::journal;
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+#[test]
+fn test_merge_state_resolve_and_continue() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_continue");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let state = git::get_merge_state(repo_path.clone()).expect("Failed to get merge state");
+    assert!(!state.in_progress);
+    assert!(git::merge_continue(repo_path.clone(), None).is_err(), "Nothing to continue");
+
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+    let original = result.commit_id.clone().unwrap();
+
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let conflict_tip = repo.revparse_single("conflict-branch").unwrap().id().to_string();
+
+    let state = git::get_merge_state(repo_path.clone()).expect("Failed to get merge state");
+    assert!(state.in_progress);
+    assert_eq!(state.merge_heads, vec![conflict_tip.clone()]);
+    assert_eq!(state.conflicted_files, vec!["README.md"]);
+    assert_eq!(state.orig_head, Some(original.clone()));
+    assert!(state.message.unwrap().starts_with("Merge branch 'conflict-branch'"));
+
+    // Conflicts must be resolved before continuing
+    assert!(git::merge_continue(repo_path.clone(), None).is_err());
+    assert!(git::mark_resolved(repo_path.clone(), "feature.txt".to_string()).is_err(), "Only conflicted files can be resolved");
+
+    fs::write(test_dir.join("README.md"), "# Resolved").expect("Failed to resolve conflict");
+    git::mark_resolved(repo_path.clone(), "README.md".to_string()).expect("Failed to mark resolved");
+    assert!(git::get_merge_state(repo_path.clone()).unwrap().conflicted_files.is_empty());
+
+    let result = git::merge_continue(repo_path.clone(), None).expect("Failed to continue merge");
+    assert_eq!(result.outcome, MergeOutcome::MergeCommit);
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    assert_eq!(result.commit_id, Some(head.id.clone()));
+    assert_eq!(head.parent_ids, vec![original, conflict_tip]);
+    assert!(head.summary.starts_with("Merge branch 'conflict-branch'"));
+    assert!(!git::get_merge_state(repo_path.clone()).unwrap().in_progress);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_merge_abort() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_abort");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+
+    git::merge_abort(repo_path.clone()).expect("Failed to abort merge");
+    assert!(!git::get_merge_state(repo_path.clone()).unwrap().in_progress);
+    assert!(!test_dir.join(".git/MERGE_HEAD").exists());
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty() && status.unstaged.is_empty(), "Abort should leave a clean tree");
+
+    assert!(git::merge_abort(repo_path.clone()).is_err(), "Nothing left to abort");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_merge_abort_snapshots_conflicted_work() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_abort_snapshot");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    fs::write(test_dir.join("README.md"), "# Half resolved").expect("Failed to write file");
+    git::merge_abort(repo_path.clone()).expect("Failed to abort merge");
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+
+    // The work in progress is kept in a snapshot, and undoing the abort brings it back
+    let entry = journal::get_undo_journal(repo_path.clone()).unwrap().pop().expect("Abort should be journalled");
+    let snapshot_id = entry.snapshot.expect("Abort should snapshot the conflicted work");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let blob = repo.revparse_single(&format!("{}:README.md", snapshot_id)).and_then(|obj| obj.peel_to_blob()).unwrap();
+    assert_eq!(blob.content(), b"# Half resolved");
+
+    journal::undo_last_operation(repo_path.clone()).expect("Failed to undo");
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Half resolved");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_commit_refused_mid_merge() {
+    let test_dir = test_utils::create_multi_branch_repository("commit_mid_merge");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+    fs::write(test_dir.join("README.md"), "# Resolved").expect("Failed to resolve conflict");
+    git::mark_resolved(repo_path.clone(), "README.md".to_string()).expect("Failed to mark resolved");
+
+    // A plain commit would drop MERGE_HEAD and leave the merge in progress
+    let error = git::create_commit(repo_path.clone(), "Resolve".to_string(), None).expect_err("Commit should be refused mid-merge");
+    assert!(error.to_string().contains("merge_continue"), "{}", error);
+    assert_eq!(git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].id, result.commit_id.unwrap());
+    assert!(git::get_merge_state(repo_path.clone()).unwrap().in_progress);
+
+    let result = git::merge_continue(repo_path.clone(), None).expect("Failed to continue merge");
+    assert_eq!(result.outcome, MergeOutcome::MergeCommit);
+    assert_eq!(git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].parent_ids.len(), 2);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,100 @@
+use crate::git::{self, MergeMode, MergeOptions, MergeOutcome};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+fn options(mode: MergeMode, message: Option<&str>) -> Option<MergeOptions> {
+    Some(MergeOptions { mode, message: message.map(|m| m.to_string()) })
+}
+
+fn head_commit_id(test_dir: &Path) -> String {
+    let repo = Repository::open(test_dir).expect("Failed to open repository");
+    let id = repo.head().unwrap().target().unwrap();
+    id.to_string()
+}
+
+/// Adds a branch one commit ahead of main, leaving main checked out
+fn create_ahead_branch(test_dir: &Path) -> String {
+    test_utils::run_git_command(&["checkout", "-b", "ahead"], &test_dir.to_path_buf());
+    fs::write(test_dir.join("ahead.txt"), "Ahead").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "ahead.txt"], &test_dir.to_path_buf());
+    test_utils::run_git_command(&["commit", "-m", "Add ahead"], &test_dir.to_path_buf());
+    let tip = head_commit_id(test_dir);
+    test_utils::run_git_command(&["checkout", "main"], &test_dir.to_path_buf());
+    tip
+}
+
+#[test]
+fn test_merge_fast_forward_and_no_ff() {
+    let test_dir = test_utils::create_test_repository("merge_fast_forward");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let ahead = create_ahead_branch(&test_dir);
+    let original = head_commit_id(&test_dir);
+
+    // The analysis is honoured: no merge commit when a fast-forward will do
+    let result = git::merge_branch(repo_path.clone(), "ahead".to_string(), None).expect("Failed to merge");
+    assert!(result.success);
+    assert_eq!(result.outcome, MergeOutcome::FastForward);
+    assert_eq!(result.commit_id, Some(ahead.clone()));
+    assert_eq!(head_commit_id(&test_dir), ahead);
+    assert!(test_dir.join("ahead.txt").exists());
+
+    let result = git::merge_branch(repo_path.clone(), "ahead".to_string(), None).expect("Failed to merge");
+    assert_eq!(result.outcome, MergeOutcome::UpToDate);
+
+    // No-ff records a merge commit with the requested message
+    test_utils::run_git_command(&["reset", "--hard", &original], &test_dir);
+    let result = git::merge_branch(repo_path.clone(), "ahead".to_string(), options(MergeMode::NoFastForward, Some("Custom merge")))
+        .expect("Failed to merge");
+    assert_eq!(result.outcome, MergeOutcome::MergeCommit);
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    assert_eq!(result.commit_id, Some(head.id.clone()));
+    assert_eq!(head.message, "Custom merge\n");
+    assert_eq!(head.parent_ids, vec![original.clone(), ahead.clone()]);
+
+    // merge.ff=false applies when no mode is requested
+    test_utils::run_git_command(&["reset", "--hard", &original], &test_dir);
+    test_utils::run_git_command(&["config", "merge.ff", "false"], &test_dir);
+    let result = git::merge_branch(repo_path.clone(), "ahead".to_string(), None).expect("Failed to merge");
+    assert_eq!(result.outcome, MergeOutcome::MergeCommit);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_merge_ff_only_and_squash() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_squash");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let original = head_commit_id(&test_dir);
+
+    // main and feature have diverged
+    let error = git::merge_branch(repo_path.clone(), "feature".to_string(), options(MergeMode::FastForwardOnly, None))
+        .expect_err("Fast-forward only should refuse diverged branches");
+    assert!(error.to_string().contains("diverged"), "{}", error);
+    assert_eq!(head_commit_id(&test_dir), original);
+
+    // Squash stages the changes without committing or recording a merge
+    let result = git::merge_branch(repo_path.clone(), "feature".to_string(), options(MergeMode::Squash, None))
+        .expect("Failed to squash merge");
+    assert!(result.success);
+    assert_eq!(result.outcome, MergeOutcome::Squashed);
+    assert_eq!(head_commit_id(&test_dir), original, "Squash merge should not move HEAD");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.len(), 1);
+    assert_eq!(status.staged[0].path, "feature.txt");
+    assert!(!test_dir.join(".git/MERGE_HEAD").exists());
+    let squash_msg = fs::read_to_string(test_dir.join(".git/SQUASH_MSG")).expect("SQUASH_MSG should be written");
+    assert!(squash_msg.starts_with("Squashed commit of the following:"));
+    assert!(squash_msg.contains("    Add feature"));
+
+    // A conflicting merge keeps the custom message for later
+    test_utils::run_git_command(&["reset", "--hard", &original], &test_dir);
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), options(MergeMode::Auto, Some("Merge with care")))
+        .expect("Failed to merge");
+    assert_eq!(result.outcome, MergeOutcome::Conflicts);
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(fs::read_to_string(test_dir.join(".git/MERGE_MSG")).unwrap(), "Merge with care");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
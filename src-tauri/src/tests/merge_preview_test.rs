@@ -0,0 +1,113 @@
+use crate::git::{self, MergeOutcome, MergePreviewOutcome};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+fn head_commit_id(repo_path: &str) -> String {
+    let repo = Repository::open(repo_path).expect("Failed to open repository");
+    let id = repo.head().unwrap().target().unwrap();
+    id.to_string()
+}
+
+#[test]
+fn test_preview_merge_outcomes() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_preview");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let original = head_commit_id(&repo_path);
+
+    let preview = git::preview_merge(repo_path.clone(), "feature".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.outcome, MergePreviewOutcome::Clean);
+    assert!(!preview.fast_forward_possible);
+    assert!(preview.conflicted_files.is_empty());
+    assert_eq!(preview.changed_files.len(), 1);
+    assert_eq!(preview.changed_files[0].path, "feature.txt");
+    assert_eq!(preview.changed_files[0].status, "new");
+    assert_eq!((preview.insertions, preview.deletions), (1, 0));
+
+    let preview = git::preview_merge(repo_path.clone(), "conflict-branch".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.outcome, MergePreviewOutcome::Conflicts);
+    assert_eq!(preview.conflicted_files, vec!["README.md"]);
+
+    // Previewing leaves no trace in the repository
+    assert_eq!(head_commit_id(&repo_path), original);
+    assert!(!test_dir.join(".git/MERGE_HEAD").exists());
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty() && status.unstaged.is_empty());
+
+    // From feature's point of view main is an ancestor only after merging it
+    git::merge_branch(repo_path.clone(), "feature".to_string(), None).expect("Failed to merge");
+    let preview = git::preview_merge(repo_path.clone(), "feature".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.outcome, MergePreviewOutcome::UpToDate);
+    assert!(preview.changed_files.is_empty());
+
+    test_utils::run_git_command(&["checkout", "feature"], &test_dir);
+    let preview = git::preview_merge(repo_path.clone(), "main".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.outcome, MergePreviewOutcome::FastForward);
+    assert!(preview.fast_forward_possible);
+    assert!(preview.changed_files.iter().any(|f| f.path == "README.md" && f.status == "modified"));
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_merge_with_unrelated_dirty_files() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_dirty");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    // A local edit to a file the merge touches blocks it
+    fs::write(test_dir.join("feature.txt"), "Untracked copy").expect("Failed to write file");
+    let preview = git::preview_merge(repo_path.clone(), "feature".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.blocking_files, vec!["feature.txt"]);
+    let error = git::merge_branch(repo_path.clone(), "feature".to_string(), None)
+        .expect_err("Merge should refuse to overwrite local changes");
+    assert!(error.to_string().contains("feature.txt"), "{}", error);
+    fs::remove_file(test_dir.join("feature.txt")).expect("Failed to remove file");
+
+    // Staged changes always block because they would end up in the merge commit
+    fs::write(test_dir.join("staged.txt"), "Staged").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "staged.txt"], &test_dir);
+    let preview = git::preview_merge(repo_path.clone(), "feature".to_string()).expect("Failed to preview merge");
+    assert_eq!(preview.blocking_files, vec!["staged.txt"]);
+    test_utils::run_git_command(&["reset", "-q", "staged.txt"], &test_dir);
+
+    // Unrelated edits and untracked files do not
+    fs::write(test_dir.join("README.md"), "# Local edit").expect("Failed to write file");
+    let preview = git::preview_merge(repo_path.clone(), "feature".to_string()).expect("Failed to preview merge");
+    assert!(preview.blocking_files.is_empty(), "{:?}", preview.blocking_files);
+    let result = git::merge_branch(repo_path.clone(), "feature".to_string(), None).expect("Failed to merge");
+    assert_eq!(result.outcome, MergeOutcome::MergeCommit);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Local edit");
+    assert_eq!(fs::read_to_string(test_dir.join("staged.txt")).unwrap(), "Staged");
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let tree = repo.find_commit(git2::Oid::from_str(&head.id).unwrap()).unwrap().tree().unwrap();
+    assert!(tree.get_name("staged.txt").is_none(), "Untracked files stay out of the merge commit");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_merge_abort_keeps_unrelated_changes() {
+    let test_dir = test_utils::create_multi_branch_repository("merge_abort_dirty");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("notes.txt"), "Notes").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "notes.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add notes"], &test_dir);
+    fs::write(test_dir.join("notes.txt"), "Local notes edit").expect("Failed to write file");
+
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+
+    git::merge_abort(repo_path.clone()).expect("Failed to abort merge");
+    assert!(!git::get_merge_state(repo_path.clone()).unwrap().in_progress);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    assert_eq!(fs::read_to_string(test_dir.join("notes.txt")).unwrap(), "Local notes edit");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty());
+    assert_eq!(status.unstaged.len(), 1);
+    assert_eq!(status.unstaged[0].path, "notes.txt");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
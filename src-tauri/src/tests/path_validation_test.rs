@@ -0,0 +1,85 @@
+use crate::blame;
+use crate::conflicts::{self, ConflictResolution};
+use crate::error::JanusError;
+use crate::git;
+use crate::history;
+use crate::paths;
+use crate::test_utils;
+use crate::tree;
+use git2::Repository;
+use std::fs;
+
+fn assert_rejected<T: std::fmt::Debug>(result: Result<T, JanusError>) {
+    match result {
+        Err(JanusError::ValidationError(_)) => {}
+        other => panic!("Expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_path_normalization_and_escapes() {
+    let test_dir = test_utils::create_test_repository("path_validation");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    fs::create_dir_all(test_dir.join("src")).expect("Failed to create directory");
+
+    assert_eq!(paths::validate_file_path(&repo, "src/../README.md").unwrap(), "README.md");
+    assert_eq!(paths::validate_file_path(&repo, "./src/new.rs").unwrap(), "src/new.rs");
+    assert_eq!(paths::validate_dir_path(&repo, ".").unwrap(), "");
+    let absolute = test_dir.join("src/main.rs").to_string_lossy().to_string();
+    assert_eq!(paths::validate_file_path(&repo, &absolute).unwrap(), "src/main.rs");
+
+    assert_rejected(paths::validate_file_path(&repo, "../outside.txt"));
+    assert_rejected(paths::validate_file_path(&repo, "src/../../outside.txt"));
+    assert_rejected(paths::validate_file_path(&repo, "/etc/passwd"));
+    assert_rejected(paths::validate_file_path(&repo, "--output=/tmp/x"));
+    assert_rejected(paths::validate_cli_arg("-p"));
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinks_out_of_worktree() {
+    let test_dir = test_utils::create_test_repository("path_validation_symlinks");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+
+    // Symlinked directories may not lead out of the worktree, but symlinked files are just content
+    let outside = std::env::temp_dir();
+    std::os::unix::fs::symlink(&outside, test_dir.join("escape")).expect("Failed to create symlink");
+    assert_rejected(paths::validate_dir_path(&repo, "escape"));
+    assert_rejected(paths::validate_file_path(&repo, "escape/file.txt"));
+    assert_eq!(paths::validate_file_path(&repo, "escape").unwrap(), "escape");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_commands_reject_unsafe_paths() {
+    let test_dir = test_utils::create_test_repository("path_validation_commands");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let escape = "../../etc".to_string();
+
+    assert_rejected(git::list_files(repo_path.clone(), Some(escape.clone()), None));
+    assert_rejected(git::stage_file(repo_path.clone(), "--all".to_string()));
+    assert_rejected(git::unstage_file(repo_path.clone(), escape.clone()));
+    assert_rejected(git::get_diff(repo_path.clone(), escape.clone(), false));
+    assert_rejected(git::mark_resolved(repo_path.clone(), escape.clone()));
+    assert_rejected(conflicts::get_conflict(repo_path.clone(), escape.clone()));
+    assert_rejected(conflicts::resolve_conflict(repo_path.clone(), "../evil.txt".to_string(), ConflictResolution::Ours));
+    assert_rejected(blame::get_blame(repo_path.clone(), escape.clone(), None, None));
+    assert_rejected(history::get_file_history(repo_path.clone(), escape.clone(), None, None, None));
+    assert_rejected(tree::list_tree(repo_path.clone(), None, Some(escape.clone())));
+    assert_rejected(tree::read_blob(repo_path.clone(), None, escape.clone(), None));
+
+    // Nothing was staged by the option-like argument
+    fs::write(test_dir.join("new.txt"), "New").expect("Failed to write file");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty());
+
+    // Normal paths keep working
+    git::stage_file(repo_path.clone(), "./new.txt".to_string()).expect("Failed to stage file");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged[0].path, "new.txt");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
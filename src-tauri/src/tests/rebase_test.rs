@@ -0,0 +1,96 @@
+use crate::git;
+use crate::rebase;
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+fn branch_tip(test_dir: &Path, branch: &str) -> String {
+    let repo = Repository::open(test_dir).expect("Failed to open repository");
+    let id = repo.revparse_single(branch).expect("Failed to resolve branch").id();
+    id.to_string()
+}
+
+#[test]
+fn test_rebase_onto_upstream() {
+    let test_dir = test_utils::create_multi_branch_repository("rebase_clean");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    test_utils::run_git_command(&["checkout", "feature"], &test_dir);
+
+    let result = rebase::rebase(repo_path.clone(), "main".to_string(), None).expect("Failed to rebase");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.total_steps, 1);
+    assert_eq!(result.created_commits.len(), 1);
+
+    // The branch itself moves, and HEAD stays attached to it
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+    let tip = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(tip.id().to_string(), result.created_commits[0]);
+    assert_eq!(tip.parent_id(0).unwrap().to_string(), branch_tip(&test_dir, "main"));
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+
+    let status = rebase::get_rebase_status(repo_path.clone()).expect("Failed to get rebase status");
+    assert!(!status.in_progress);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_rebase_conflicts_continue_skip_abort() {
+    let test_dir = test_utils::create_multi_branch_repository("rebase_conflicts");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    test_utils::run_git_command(&["checkout", "conflict-branch"], &test_dir);
+    fs::write(test_dir.join("extra.txt"), "Extra").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "extra.txt"], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add extra"], &test_dir);
+    let original = branch_tip(&test_dir, "conflict-branch");
+    let main = branch_tip(&test_dir, "main");
+
+    // The first commit conflicts with main and stops the rebase
+    let result = rebase::rebase(repo_path.clone(), "main".to_string(), None).expect("Failed to rebase");
+    assert!(result.has_conflicts);
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(result.current_step, Some(1));
+    assert_eq!(result.total_steps, 2);
+
+    // The status is read back from disk
+    let status = rebase::get_rebase_status(repo_path.clone()).expect("Failed to get rebase status");
+    assert!(status.in_progress);
+    assert_eq!(status.head_name.as_deref(), Some("conflict-branch"));
+    assert_eq!(status.orig_head, Some(original.clone()));
+    assert_eq!(status.onto, Some(main.clone()));
+    assert_eq!(status.current_step, Some(1));
+    assert_eq!(status.conflicted_files, vec!["README.md"]);
+
+    assert!(rebase::rebase(repo_path.clone(), "main".to_string(), None).is_err(), "A second rebase should be refused");
+    assert!(rebase::rebase_continue(repo_path.clone()).is_err(), "Continue needs resolved conflicts");
+
+    // Abort restores the branch
+    rebase::rebase_abort(repo_path.clone()).expect("Failed to abort");
+    assert_eq!(branch_tip(&test_dir, "HEAD"), original);
+    assert!(!rebase::get_rebase_status(repo_path.clone()).unwrap().in_progress);
+
+    // Resolve and continue replays the rest
+    rebase::rebase(repo_path.clone(), "main".to_string(), None).expect("Failed to rebase");
+    fs::write(test_dir.join("README.md"), "# Resolved").expect("Failed to resolve conflict");
+    git::stage_file(repo_path.clone(), "README.md".to_string()).expect("Failed to stage resolution");
+    let result = rebase::rebase_continue(repo_path.clone()).expect("Failed to continue");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.created_commits.len(), 2);
+    let summaries: Vec<String> = git::get_commits(repo_path.clone(), None, Some(3)).unwrap()
+        .into_iter().map(|c| c.summary).collect();
+    assert_eq!(summaries, vec!["Add extra", "Modify README in conflict branch", "Modify README in main branch"]);
+
+    // Skip drops the conflicting commit
+    test_utils::run_git_command(&["reset", "--hard", &original], &test_dir);
+    rebase::rebase(repo_path.clone(), "main".to_string(), None).expect("Failed to rebase");
+    let result = rebase::rebase_skip(repo_path.clone()).expect("Failed to skip");
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.created_commits.len(), 1);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+    assert!(test_dir.join("extra.txt").exists());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,58 @@
+use crate::git::{self, ResetMode};
+use crate::reflog;
+use crate::test_utils;
+use std::fs;
+
+#[test]
+fn test_reflog_recovers_reset_commit() {
+    let test_dir = test_utils::create_test_repository("reflog_recover");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("feature.txt"), "Feature").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    let lost = git::create_commit(repo_path.clone(), "Add feature".to_string(), None).expect("Failed to create commit");
+    test_utils::run_git_command(&["reset", "--hard", "HEAD~1"], &test_dir);
+
+    let entries = reflog::get_reflog(repo_path.clone(), None).expect("Failed to read reflog");
+    assert!(entries.len() >= 2);
+    assert_eq!(entries[0].index, 0);
+    assert_eq!(entries[0].old_id, lost.id);
+    assert!(entries[0].message.starts_with("reset"), "Unexpected message {}", entries[0].message);
+    assert_eq!(entries[1].new_id, lost.id);
+
+    let dangling = reflog::find_dangling_commits(repo_path.clone(), None).expect("Failed to find dangling commits");
+    assert_eq!(dangling.len(), 1);
+    assert_eq!(dangling[0].id, lost.id);
+
+    let branch = reflog::create_branch_at_reflog_entry(repo_path.clone(), None, 1, "recovered".to_string())
+        .expect("Failed to create branch");
+    assert_eq!(branch.commit_id, lost.id);
+    assert!(reflog::find_dangling_commits(repo_path.clone(), None).unwrap().is_empty());
+
+    // The new branch has its own reflog
+    let branch_entries = reflog::get_reflog(repo_path.clone(), Some("recovered".to_string())).expect("Failed to read reflog");
+    assert_eq!(branch_entries[0].new_id, lost.id);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_reset_to_reflog_entry() {
+    let test_dir = test_utils::create_test_repository("reflog_reset");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("feature.txt"), "Feature").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    let lost = git::create_commit(repo_path.clone(), "Add feature".to_string(), None).expect("Failed to create commit");
+    git::reset_to(repo_path.clone(), "HEAD~1".to_string(), ResetMode::Hard).expect("Failed to reset");
+    assert!(!test_dir.join("feature.txt").exists());
+
+    let result = reflog::reset_to_reflog_entry(repo_path.clone(), None, 1, ResetMode::Hard).expect("Failed to reset");
+    assert_eq!(result.new_head, lost.id);
+    assert!(test_dir.join("feature.txt").exists());
+
+    assert!(reflog::reset_to_reflog_entry(repo_path.clone(), None, 99, ResetMode::Hard).is_err());
+    assert!(reflog::get_reflog(repo_path.clone(), Some("no-such-branch".to_string())).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,97 @@
+use crate::git;
+use crate::rebase;
+use crate::repo_state::{self, RepoOperation};
+use crate::sequencer;
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+fn branch_tip(test_dir: &Path, branch: &str) -> String {
+    let repo = Repository::open(test_dir).expect("Failed to open repository");
+    let id = repo.revparse_single(branch).expect("Failed to resolve branch").id();
+    id.to_string()
+}
+
+#[test]
+fn test_merge_and_rebase_state() {
+    let test_dir = test_utils::create_multi_branch_repository("repo_state_merge");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    assert_eq!(state.operation, RepoOperation::None);
+    assert!(state.conflicted_files.is_empty());
+
+    git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    assert_eq!(state.operation, RepoOperation::Merge { merge_heads: vec![branch_tip(&test_dir, "conflict-branch")] });
+    assert_eq!(state.conflicted_files, vec!["README.md"]);
+
+    // get_status carries the same state for the banner
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.state, state);
+    git::merge_abort(repo_path.clone()).expect("Failed to abort merge");
+
+    test_utils::run_git_command(&["checkout", "conflict-branch"], &test_dir);
+    rebase::rebase(repo_path.clone(), "main".to_string(), None).expect("Failed to rebase");
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    match state.operation {
+        RepoOperation::Rebase { interactive, head_name, onto, current_step, total_steps, .. } => {
+            assert!(!interactive);
+            assert_eq!(head_name.as_deref(), Some("conflict-branch"));
+            assert_eq!(onto, Some(branch_tip(&test_dir, "main")));
+            assert_eq!((current_step, total_steps), (Some(1), 1));
+        }
+        other => panic!("Expected a rebase, got {:?}", other),
+    }
+    assert_eq!(state.conflicted_files, vec!["README.md"]);
+    rebase::rebase_abort(repo_path.clone()).expect("Failed to abort rebase");
+    assert_eq!(repo_state::get_repository_state(repo_path.clone()).unwrap().operation, RepoOperation::None);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_sequencer_and_bisect_state() {
+    let test_dir = test_utils::create_multi_branch_repository("repo_state_sequencer");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let conflict_tip = branch_tip(&test_dir, "conflict-branch");
+
+    // The app's own cherry-pick sequence
+    sequencer::cherry_pick(repo_path.clone(), vec![conflict_tip.clone(), "feature".to_string()], None)
+        .expect("Failed to cherry-pick");
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    assert_eq!(state.operation, RepoOperation::CherryPick { current_commit: Some(conflict_tip.clone()), remaining: 2 });
+    assert_eq!(state.conflicted_files, vec!["README.md"]);
+    sequencer::cherry_pick_abort(repo_path.clone()).expect("Failed to abort cherry-pick");
+
+    // A single revert started from the command line
+    fs::write(test_dir.join("README.md"), "# Changed again").expect("Failed to write file");
+    test_utils::run_git_command(&["commit", "-am", "Change README again"], &test_dir);
+    let output = std::process::Command::new("git")
+        .args(["revert", "--no-edit", "HEAD~1"])
+        .current_dir(&test_dir)
+        .output()
+        .expect("Failed to run git revert");
+    assert!(!output.status.success(), "The revert should conflict");
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    assert_eq!(state.operation, RepoOperation::Revert { current_commit: Some(branch_tip(&test_dir, "HEAD~1")), remaining: 1 });
+    test_utils::run_git_command(&["revert", "--abort"], &test_dir);
+
+    test_utils::run_git_command(&["bisect", "start"], &test_dir);
+    test_utils::run_git_command(&["bisect", "bad"], &test_dir);
+    test_utils::run_git_command(&["bisect", "good", "HEAD~2"], &test_dir);
+    let state = repo_state::get_repository_state(repo_path.clone()).expect("Failed to get state");
+    assert_eq!(
+        state.operation,
+        RepoOperation::Bisect {
+            start: Some("main".to_string()),
+            bad: Some(branch_tip(&test_dir, "main")),
+            good: vec![branch_tip(&test_dir, "main~2")],
+        }
+    );
+    assert!(state.conflicted_files.is_empty());
+    test_utils::run_git_command(&["bisect", "reset"], &test_dir);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
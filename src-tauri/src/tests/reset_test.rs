@@ -0,0 +1,113 @@
+use crate::git::{self, ResetMode};
+use crate::snapshot;
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+#[test]
+fn test_reset_soft_and_mixed() {
+    let test_dir = test_utils::create_test_repository("reset_soft_mixed");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let base = git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].id.clone();
+    fs::write(test_dir.join("feature.txt"), "Feature").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    let feature = git::create_commit(repo_path.clone(), "Add feature".to_string(), None)
+        .expect("Failed to create commit");
+
+    // Soft reset keeps the change staged
+    let result = git::reset_to(repo_path.clone(), "HEAD~1".to_string(), ResetMode::Soft)
+        .expect("Failed to soft reset");
+    assert_eq!(result.previous_head, feature.id, "Previous HEAD should be returned");
+    assert_eq!(result.new_head, base);
+    assert!(result.snapshot.is_none(), "Soft reset should not snapshot");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.len(), 1, "Change should remain staged");
+
+    // Mixed reset keeps the change in the working tree only
+    git::reset_to(repo_path.clone(), feature.id.clone(), ResetMode::Soft).expect("Failed to reset forward");
+    git::reset_to(repo_path.clone(), base.clone(), ResetMode::Mixed).expect("Failed to mixed reset");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert!(status.staged.is_empty(), "Nothing should be staged after a mixed reset");
+    assert_eq!(status.unstaged.len(), 1, "File should be left in the working tree");
+
+    assert!(git::reset_to(repo_path.clone(), "no-such-rev".to_string(), ResetMode::Soft).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_hard_reset_snapshots_uncommitted_work() {
+    let test_dir = test_utils::create_test_repository("reset_hard_snapshot");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("feature.txt"), "Feature").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    git::create_commit(repo_path.clone(), "Add feature".to_string(), None).expect("Failed to create commit");
+
+    // A clean tree has nothing to snapshot
+    let result = git::reset_to(repo_path.clone(), "HEAD".to_string(), ResetMode::Hard)
+        .expect("Failed to hard reset");
+    assert!(result.snapshot.is_none(), "Clean tree should not be snapshotted");
+
+    fs::write(test_dir.join("feature.txt"), "Staged edit").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    fs::write(test_dir.join("notes.txt"), "Untracked notes").expect("Failed to write file");
+
+    let result = git::reset_to(repo_path.clone(), "HEAD~1".to_string(), ResetMode::Hard)
+        .expect("Failed to hard reset");
+    let snapshot_id = result.snapshot.expect("Hard reset should snapshot uncommitted work");
+    assert!(!test_dir.join("feature.txt").exists());
+
+    let snapshots = snapshot::list_snapshots(repo_path.clone()).expect("Failed to list snapshots");
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].id, snapshot_id);
+    assert_eq!(snapshots[0].base_commit, result.previous_head, "Snapshot should be based on the old HEAD");
+
+    // The snapshot is stash-shaped, so git itself can inspect it
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let blob_text = |spec: &str| {
+        let blob = repo.revparse_single(spec).and_then(|obj| obj.peel_to_blob()).expect("Failed to find blob");
+        String::from_utf8_lossy(blob.content()).to_string()
+    };
+    assert_eq!(blob_text(&format!("{}:notes.txt", snapshot_id)), "Untracked notes");
+    assert_eq!(blob_text(&format!("{}^2:feature.txt", snapshot_id)), "Staged edit", "Index commit should hold the staged content");
+
+    // Going back to the old HEAD and restoring recovers the work
+    git::reset_to(repo_path.clone(), result.previous_head.clone(), ResetMode::Hard).expect("Failed to reset back");
+    snapshot::restore_snapshot(repo_path.clone(), snapshot_id).expect("Failed to restore snapshot");
+    assert_eq!(fs::read_to_string(test_dir.join("feature.txt")).unwrap(), "Staged edit");
+    assert_eq!(fs::read_to_string(test_dir.join("notes.txt")).unwrap(), "Untracked notes");
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), vec!["feature.txt"], "Staged work should be staged again");
+    assert_eq!(status.unstaged.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), vec!["notes.txt"], "Untracked files stay untracked");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_hard_reset_during_conflicted_merge() {
+    let test_dir = test_utils::create_multi_branch_repository("reset_hard_conflicted");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    let merge = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(merge.has_conflicts);
+    let conflicted = fs::read_to_string(test_dir.join("README.md")).unwrap();
+
+    // The conflicted index cannot be written as a tree, which must not stop the reset
+    let result = git::reset_to(repo_path.clone(), "HEAD".to_string(), ResetMode::Hard)
+        .expect("Hard reset should work while a merge is conflicted");
+    let snapshot_id = result.snapshot.expect("Conflicted work should be snapshotted");
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified in main branch");
+
+    // The snapshot keeps the file with its conflict markers, and "ours" as the staged side
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let blob_text = |spec: &str| {
+        let blob = repo.revparse_single(spec).and_then(|obj| obj.peel_to_blob()).expect("Failed to find blob");
+        String::from_utf8_lossy(blob.content()).to_string()
+    };
+    assert_eq!(blob_text(&format!("{}:README.md", snapshot_id)), conflicted);
+    assert_eq!(blob_text(&format!("{}^2:README.md", snapshot_id)), "# Modified in main branch");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
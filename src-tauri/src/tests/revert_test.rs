@@ -0,0 +1,81 @@
+use crate::error::JanusError;
+use crate::git;
+use crate::sequencer;
+use crate::test_utils;
+use std::fs;
+
+#[test]
+fn test_revert_commit_and_no_commit() {
+    let test_dir = test_utils::create_test_repository("revert_commit");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    fs::write(test_dir.join("mistake.txt"), "Oops").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "mistake.txt".to_string()).expect("Failed to stage file");
+    let mistake = git::create_commit(repo_path.clone(), "Add mistake".to_string(), None)
+        .expect("Failed to create commit");
+    
+    // No-commit mode stages the inverse change without moving HEAD
+    let result = sequencer::revert_commit(repo_path.clone(), mistake.id.clone(), None, Some(true))
+        .expect("Failed to revert");
+    assert!(result.success && result.created_commits.is_empty());
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.len(), 1);
+    assert_eq!(status.staged[0].status, "deleted");
+    assert_eq!(git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].id, mistake.id);
+    test_utils::run_git_command(&["reset", "--hard", "HEAD"], &test_dir);
+    
+    // A normal revert creates a commit with the standard message
+    let result = sequencer::revert_commit(repo_path.clone(), mistake.id.clone(), None, None)
+        .expect("Failed to revert");
+    assert!(result.success, "{}", result.message);
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    assert_eq!(result.created_commits, vec![head.id.clone()]);
+    assert_eq!(head.message, format!("Revert \"Add mistake\"\n\nThis reverts commit {}.\n", mistake.id));
+    assert!(!test_dir.join("mistake.txt").exists());
+    
+    // Mainline is rejected for ordinary commits
+    assert!(sequencer::revert_commit(repo_path.clone(), mistake.id.clone(), Some(1), None).is_err());
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_revert_conflicts_continue_and_abort() {
+    let test_dir = test_utils::create_multi_branch_repository("revert_conflicts");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    // A later change to the same line makes reverting the earlier one conflict
+    let target = git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].id.clone();
+    fs::write(test_dir.join("README.md"), "# Modified again").expect("Failed to write README");
+    git::stage_file(repo_path.clone(), "README.md".to_string()).expect("Failed to stage README");
+    let latest = git::create_commit(repo_path.clone(), "Modify README again".to_string(), None)
+        .expect("Failed to create commit");
+    
+    let result = sequencer::revert_commit(repo_path.clone(), target.clone(), None, None).expect("Failed to revert");
+    assert!(result.has_conflicts && !result.success);
+    assert_eq!(result.conflicted_files, vec!["README.md"]);
+    assert_eq!(result.current_commit, Some(target.clone()));
+    assert!(test_dir.join(".git/REVERT_HEAD").exists());
+    
+    sequencer::revert_abort(repo_path.clone()).expect("Failed to abort");
+    assert_eq!(git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0].id, latest.id);
+    assert_eq!(fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Modified again");
+    assert!(!test_dir.join(".git/REVERT_HEAD").exists());
+    
+    // Resolve and continue to record the revert
+    sequencer::revert_commit(repo_path.clone(), target.clone(), None, None).expect("Failed to revert");
+    fs::write(test_dir.join("README.md"), "# Test Repository").expect("Failed to resolve");
+    git::stage_file(repo_path.clone(), "README.md".to_string()).expect("Failed to stage resolution");
+    let error = sequencer::cherry_pick_continue(repo_path.clone()).expect_err("A revert is not a cherry-pick");
+    assert!(matches!(error, JanusError::ValidationError(_)), "{:?}", error);
+    assert!(test_dir.join(".git/REVERT_HEAD").exists(), "The revert should still be in progress");
+    let result = sequencer::revert_continue(repo_path.clone()).expect("Failed to continue");
+    assert!(result.success, "{}", result.message);
+    
+    let head = &git::get_commits(repo_path.clone(), None, Some(1)).unwrap()[0];
+    assert_eq!(head.summary, "Revert \"Modify README in main branch\"");
+    assert_eq!(head.parent_ids, vec![latest.id]);
+    assert!(!test_dir.join(".git/sequencer").exists());
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
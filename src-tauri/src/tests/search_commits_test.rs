@@ -0,0 +1,106 @@
+use crate::search::{self, CommitSearchQuery};
+use crate::test_utils;
+use git2::Repository;
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+fn commit(test_dir: &Path, file: &str, content: &str, author: &str, message: &str) {
+    fs::write(test_dir.join(file), content).expect("Failed to write file");
+    test_utils::run_git_command(&["add", file], &test_dir.to_path_buf());
+    let author = format!("{} <{}@example.com>", author, author.to_lowercase());
+    test_utils::run_git_command(&["commit", "-m", message, "--author", &author], &test_dir.to_path_buf());
+}
+
+fn summaries(repo: &Repository, query: CommitSearchQuery) -> Vec<String> {
+    let cancel = AtomicBool::new(false);
+    let result = search::search(repo, "test", &query, &cancel, &|_| {}).expect("Failed to search");
+    result.matches.into_iter().map(|c| c.summary).collect()
+}
+
+fn create_history(name: &str) -> (std::path::PathBuf, Repository) {
+    let test_dir = test_utils::create_test_repository(name);
+    commit(&test_dir, "app.rs", "fn main() {}\n", "Alice", "Add app");
+    commit(&test_dir, "app.rs", "fn main() { helper(); }\n", "Bob", "Call helper from main");
+    commit(&test_dir, "lib.rs", "fn helper() {}\n", "Alice", "Add helper library");
+    commit(&test_dir, "lib.rs", "fn helper() { todo!() }\n", "Carol", "Fix #42 in helper");
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    (test_dir, repo)
+}
+
+#[test]
+fn test_search_commits_filters() {
+    let (test_dir, repo) = create_history("search_filters");
+
+    let query = |f: &dyn Fn(&mut CommitSearchQuery)| {
+        let mut query = CommitSearchQuery::default();
+        f(&mut query);
+        query
+    };
+
+    assert_eq!(summaries(&repo, query(&|q| q.message = Some(r"#\d+".to_string()))), vec!["Fix #42 in helper"]);
+    assert_eq!(
+        summaries(&repo, query(&|q| { q.message = Some("^add".to_string()); q.ignore_case = true; })),
+        vec!["Add helper library", "Add app"]
+    );
+    assert_eq!(summaries(&repo, query(&|q| q.author = Some("alice@".to_string()))), vec!["Add helper library", "Add app"]);
+    assert_eq!(summaries(&repo, query(&|q| q.committer = Some("Test User".to_string()))).len(), 5);
+
+    // -S only counts commits that change the number of occurrences
+    assert_eq!(
+        summaries(&repo, query(&|q| q.pickaxe = Some("helper".to_string()))),
+        vec!["Add helper library", "Call helper from main"]
+    );
+    assert!(summaries(&repo, query(&|q| q.pickaxe = Some("HELPER".to_string()))).is_empty());
+    assert_eq!(
+        summaries(&repo, query(&|q| { q.pickaxe = Some("HELPER".to_string()); q.ignore_case = true; })),
+        vec!["Add helper library", "Call helper from main"]
+    );
+    // -G matches any added or removed line
+    assert_eq!(
+        summaries(&repo, query(&|q| q.diff_regex = Some(r"helper\(\)".to_string()))),
+        vec!["Fix #42 in helper", "Add helper library", "Call helper from main"]
+    );
+
+    assert_eq!(summaries(&repo, query(&|q| q.paths = vec!["app.rs".to_string()])), vec!["Call helper from main", "Add app"]);
+    assert_eq!(
+        summaries(&repo, query(&|q| { q.range = Some("HEAD~3..HEAD~1".to_string()); q.author = Some("Bob|Alice".to_string()); })),
+        vec!["Add helper library", "Call helper from main"]
+    );
+
+    let cancel = AtomicBool::new(false);
+    assert!(search::search(&repo, "test", &query(&|q| q.message = Some("(".to_string())), &cancel, &|_| {}).is_err());
+    assert!(search::search(&repo, "test", &query(&|q| q.paths = vec!["../x".to_string()]), &cancel, &|_| {}).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_search_commits_progress_limit_and_cancel() {
+    let (test_dir, repo) = create_history("search_progress");
+
+    let events = RefCell::new(Vec::new());
+    let query = CommitSearchQuery { author: Some("Alice".to_string()), limit: Some(1), ..Default::default() };
+    let cancel = AtomicBool::new(false);
+    let result = search::search(&repo, "search-1", &query, &cancel, &|progress| events.borrow_mut().push(progress.clone()))
+        .expect("Failed to search");
+    assert_eq!(result.matches.len(), 1);
+    assert_eq!(result.matches[0].summary, "Add helper library");
+    assert_eq!(result.total, 5);
+    assert!(!result.cancelled);
+
+    let events = events.into_inner();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].search_id, "search-1");
+    assert_eq!((events[0].scanned, events[0].total), (5, 5));
+    assert_eq!(events[0].matches.len(), 1);
+
+    let cancel = AtomicBool::new(true);
+    let result = search::search(&repo, "search-2", &CommitSearchQuery::default(), &cancel, &|_| {}).expect("Failed to search");
+    assert!(result.cancelled);
+    assert!(result.matches.is_empty());
+    assert_eq!(result.scanned, 0);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
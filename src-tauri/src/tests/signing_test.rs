@@ -0,0 +1,142 @@
+use crate::git::{self, CommitOptions};
+use crate::signing::{self, SignatureStatus, SigningFormat};
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_ssh_commit_and_tag_signing() {
+    let test_dir = test_utils::create_test_repository("ssh_signing");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    // Generate a throwaway signing key inside the git dir so it is not tracked
+    let key_path = test_dir.join(".git/signing_key");
+    let status = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "test@example.com", "-f"])
+        .arg(&key_path)
+        .status()
+        .expect("Failed to run ssh-keygen");
+    assert!(status.success(), "ssh-keygen should generate a key");
+    
+    let public_key = fs::read_to_string(key_path.with_extension("pub")).expect("Failed to read public key");
+    let allowed_signers = test_dir.join(".git/allowed_signers");
+    fs::write(&allowed_signers, format!("test@example.com {}", public_key)).expect("Failed to write allowed signers");
+    
+    let key_arg = key_path.to_string_lossy().to_string();
+    let allowed_arg = allowed_signers.to_string_lossy().to_string();
+    test_utils::run_git_command(&["config", "gpg.format", "ssh"], &test_dir);
+    test_utils::run_git_command(&["config", "user.signingkey", &key_arg], &test_dir);
+    test_utils::run_git_command(&["config", "gpg.ssh.allowedSignersFile", &allowed_arg], &test_dir);
+    
+    // The initial commit predates the signing configuration
+    let initial = git::get_commit_signature(repo_path.clone(), "HEAD".to_string()).expect("Failed to verify");
+    assert_eq!(initial.status, SignatureStatus::Unsigned);
+    
+    // commit.gpgsign makes create_commit sign
+    test_utils::run_git_command(&["config", "commit.gpgsign", "true"], &test_dir);
+    let options = CommitOptions { allow_empty: true, ..Default::default() };
+    let commit = git::create_commit(repo_path.clone(), "Signed commit".to_string(), Some(options))
+        .expect("Failed to create signed commit");
+    assert_eq!(git::get_commits(repo_path.clone(), None, None).unwrap()[0].id, commit.id,
+               "HEAD should advance to the signed commit");
+    
+    let verified = git::get_commit_signature(repo_path.clone(), commit.id.clone()).expect("Failed to verify");
+    assert_eq!(verified.status, SignatureStatus::Good, "{}", verified.details);
+    assert_eq!(verified.format, Some(SigningFormat::Ssh));
+    assert_eq!(verified.signer.as_deref(), Some("test@example.com"));
+    assert!(verified.key.unwrap_or_default().starts_with("SHA256:"));
+    
+    // A signature over different data does not verify
+    let repo = Repository::open(&test_dir).expect("Failed to open repository");
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let signature = repo.signature().unwrap();
+    let buffer = repo.commit_create_buffer(&signature, &signature, "Tampered\n", &head.tree().unwrap(), &[&head]).unwrap();
+    let buffer = std::str::from_utf8(&buffer).unwrap().to_string();
+    let forged = signing::sign_buffer(&repo, &buffer.replace("Tampered", "Original")).expect("Failed to sign");
+    let tampered = repo.commit_signed(&buffer, &forged, None).expect("Failed to write commit");
+    let result = git::get_commit_signature(repo_path.clone(), tampered.to_string()).expect("Failed to verify");
+    assert_eq!(result.status, SignatureStatus::Bad);
+    
+    // Signed tags are verified the same way; lightweight tags are unsigned
+    test_utils::run_git_command(&["tag", "-s", "v1.0", "-m", "Release 1.0"], &test_dir);
+    test_utils::run_git_command(&["tag", "light"], &test_dir);
+    let tag = git::get_tag_signature(repo_path.clone(), "v1.0".to_string()).expect("Failed to verify tag");
+    assert_eq!(tag.status, SignatureStatus::Good, "{}", tag.details);
+    let light = git::get_tag_signature(repo_path.clone(), "light".to_string()).expect("Failed to verify tag");
+    assert_eq!(light.status, SignatureStatus::Unsigned);
+    
+    // Without a matching allowed signer the key is unknown
+    fs::write(&allowed_signers, "").expect("Failed to clear allowed signers");
+    let unknown = git::get_commit_signature(repo_path.clone(), commit.id).expect("Failed to verify");
+    assert_eq!(unknown.status, SignatureStatus::UnknownKey, "{}", unknown.details);
+    
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_gpg_trust_and_expiry() {
+    let test_dir = test_utils::create_test_repository("gpg_signing");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    
+    // Each keyring lives in its own home inside the git dir, reached through a wrapper program
+    let gpg = |home: &str, args: &[&str]| {
+        let home = test_dir.join(".git").join(home);
+        fs::create_dir_all(&home).expect("Failed to create gpg home");
+        Command::new("chmod").arg("700").arg(&home).status().expect("Failed to chmod gpg home");
+        Command::new("gpg").arg("--homedir").arg(&home).args(["--batch", "--passphrase", ""]).args(args)
+            .output().expect("Failed to run gpg")
+    };
+    let wrapper = test_dir.join(".git/gpg-wrapper");
+    let use_gpg = |home: &str, extra: &str| {
+        let home = test_dir.join(".git").join(home);
+        fs::write(&wrapper, format!("#!/bin/sh\nexec gpg --homedir '{}' {} \"$@\"\n", home.display(), extra))
+            .expect("Failed to write gpg wrapper");
+        Command::new("chmod").arg("+x").arg(&wrapper).status().expect("Failed to chmod gpg wrapper");
+    };
+    
+    let keygen = gpg("gnupg", &["--quick-gen-key", "Test <test@example.com>", "ed25519", "sign", "never"]);
+    assert!(keygen.status.success(), "gpg should generate a key");
+    let expiring = gpg("gnupg-old", &["--faked-system-time", "20200101T000000", "--quick-gen-key",
+                                      "Old <old@example.com>", "ed25519", "sign", "1d"]);
+    assert!(expiring.status.success(), "gpg should generate an expiring key");
+    let public_key = gpg("gnupg", &["--armor", "--export", "test@example.com"]);
+    fs::write(test_dir.join(".git/public.asc"), &public_key.stdout).expect("Failed to write public key");
+    let import_arg = test_dir.join(".git/public.asc").to_string_lossy().to_string();
+    assert!(gpg("gnupg-public", &["--import", &import_arg]).status.success(), "gpg should import the key");
+    
+    let wrapper_arg = wrapper.to_string_lossy().to_string();
+    test_utils::run_git_command(&["config", "gpg.program", &wrapper_arg], &test_dir);
+    test_utils::run_git_command(&["config", "user.signingkey", "test@example.com"], &test_dir);
+    test_utils::run_git_command(&["config", "commit.gpgsign", "true"], &test_dir);
+    let options = || Some(CommitOptions { allow_empty: true, ..Default::default() });
+    
+    // The key that made the signature is ultimately trusted in its own keyring
+    use_gpg("gnupg", "");
+    let commit = git::create_commit(repo_path.clone(), "Signed commit".to_string(), options())
+        .expect("Failed to create signed commit");
+    let verified = git::get_commit_signature(repo_path.clone(), commit.id.clone()).expect("Failed to verify");
+    assert_eq!(verified.status, SignatureStatus::Good, "{}", verified.details);
+    assert_eq!(verified.format, Some(SigningFormat::Openpgp));
+    assert_eq!(verified.signer.as_deref(), Some("Test <test@example.com>"));
+    
+    // A keyring that only imported the public key can check the signature but does not trust it
+    use_gpg("gnupg-public", "");
+    let untrusted = git::get_commit_signature(repo_path.clone(), commit.id).expect("Failed to verify");
+    assert_eq!(untrusted.status, SignatureStatus::Untrusted, "{}", untrusted.details);
+    
+    // A signature made before the key expired is still reported as expired
+    use_gpg("gnupg-old", "--faked-system-time 20200101T000100");
+    test_utils::run_git_command(&["config", "user.signingkey", "old@example.com"], &test_dir);
+    let old = git::create_commit(repo_path.clone(), "Old commit".to_string(), options())
+        .expect("Failed to create signed commit");
+    use_gpg("gnupg-old", "");
+    let expired = git::get_commit_signature(repo_path.clone(), old.id).expect("Failed to verify");
+    assert_eq!(expired.status, SignatureStatus::Expired, "{}", expired.details);
+    
+    for home in ["gnupg", "gnupg-old", "gnupg-public"] {
+        Command::new("gpgconf").arg("--homedir").arg(test_dir.join(".git").join(home))
+            .args(["--kill", "gpg-agent"]).status().ok();
+    }
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,78 @@
+use crate::submodule;
+use crate::test_utils;
+use std::fs;
+use std::path::PathBuf;
+
+/// A superproject with `libs/sub` pointing at another test repository, and a fresh clone of it
+fn create_superproject(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+    let sub_dir = test_utils::create_test_repository(&format!("{}_sub", name));
+    let super_dir = test_utils::create_test_repository(&format!("{}_super", name));
+    let url = format!("file://{}", sub_dir.display());
+    test_utils::run_git_command(&["-c", "protocol.file.allow=always", "submodule", "add", &url, "libs/sub"], &super_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add submodule"], &super_dir);
+
+    let clone_dir = std::env::temp_dir().join(format!("januslens_test_{}_clone", name));
+    let _ = fs::remove_dir_all(&clone_dir);
+    test_utils::run_git_command(&["clone", &super_dir.to_string_lossy(), &clone_dir.to_string_lossy()], &std::env::temp_dir());
+    (sub_dir, super_dir, clone_dir)
+}
+
+#[test]
+fn test_submodule_init_update_and_open() {
+    let (sub_dir, super_dir, clone_dir) = create_superproject("submodule_update");
+    let clone_path = clone_dir.to_string_lossy().to_string();
+
+    let submodules = submodule::list_submodules(clone_path.clone()).expect("Failed to list submodules");
+    assert_eq!(submodules.len(), 1);
+    let listed = &submodules[0];
+    assert_eq!(listed.path, "libs/sub");
+    assert_eq!(listed.url.as_deref(), Some(format!("file://{}", sub_dir.display()).as_str()));
+    assert!(listed.head_id.is_some());
+    assert!(!listed.initialized && !listed.checked_out);
+    assert!(submodule::open_submodule(clone_path.clone(), "libs/sub".to_string()).is_err());
+
+    let submodules = submodule::init_submodules(clone_path.clone(), None).expect("Failed to init submodules");
+    assert!(submodules[0].initialized && !submodules[0].checked_out);
+
+    let submodules = submodule::update_submodules(clone_path.clone(), Some(vec!["libs/sub".to_string()]), None)
+        .expect("Failed to update submodules");
+    let updated = &submodules[0];
+    assert!(updated.checked_out && !updated.out_of_date && !updated.dirty);
+    assert_eq!(updated.workdir_id, updated.head_id);
+    assert!(clone_dir.join("libs/sub/README.md").exists());
+
+    fs::write(clone_dir.join("libs/sub/README.md"), "# Changed").expect("Failed to write file");
+    assert!(submodule::list_submodules(clone_path.clone()).unwrap()[0].dirty);
+
+    let info = submodule::open_submodule(clone_path.clone(), "libs/sub".to_string()).expect("Failed to open submodule");
+    assert_eq!(info.name, "sub");
+    assert_eq!(fs::canonicalize(&info.path).unwrap(), fs::canonicalize(clone_dir.join("libs/sub")).unwrap());
+
+    assert!(submodule::update_submodules(clone_path.clone(), Some(vec!["README.md".to_string()]), None).is_err());
+
+    test_utils::cleanup_test_repository(&clone_dir);
+    test_utils::cleanup_test_repository(&super_dir);
+    test_utils::cleanup_test_repository(&sub_dir);
+}
+
+#[test]
+fn test_submodule_sync() {
+    let (sub_dir, super_dir, clone_dir) = create_superproject("submodule_sync");
+    let clone_path = clone_dir.to_string_lossy().to_string();
+    submodule::update_submodules(clone_path.clone(), None, Some(true)).expect("Failed to update submodules");
+
+    // The URL moves in .gitmodules; sync carries it into the config and the submodule's remote
+    let moved = format!("file://{}/moved", sub_dir.display());
+    test_utils::run_git_command(&["config", "-f", ".gitmodules", "submodule.libs/sub.url", &moved], &clone_dir);
+    let submodules = submodule::sync_submodules(clone_path.clone(), None).expect("Failed to sync submodules");
+    assert_eq!(submodules[0].url.as_deref(), Some(moved.as_str()));
+
+    let config = git2::Repository::open(&clone_dir).unwrap().config().unwrap();
+    assert_eq!(config.get_string("submodule.libs/sub.url").unwrap(), moved);
+    let sub_repo = git2::Repository::open(clone_dir.join("libs/sub")).unwrap();
+    assert_eq!(sub_repo.find_remote("origin").unwrap().url(), Some(moved.as_str()));
+
+    test_utils::cleanup_test_repository(&clone_dir);
+    test_utils::cleanup_test_repository(&super_dir);
+    test_utils::cleanup_test_repository(&sub_dir);
+}
@@ -0,0 +1,101 @@
+use crate::test_utils;
+use crate::tree;
+use git2::Repository;
+use std::fs;
+
+#[test]
+fn test_list_tree_at_revision() {
+    let test_dir = test_utils::create_test_repository("list_tree");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::create_dir_all(test_dir.join("src/nested")).expect("Failed to create directories");
+    fs::write(test_dir.join("src/main.rs"), "fn main() {}\n").expect("Failed to write file");
+    fs::write(test_dir.join("src/nested/mod.rs"), "").expect("Failed to write file");
+    std::os::unix::fs::symlink("README.md", test_dir.join("link")).expect("Failed to create symlink");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    let head = Repository::open(&test_dir).unwrap().head().unwrap().target().unwrap().to_string();
+    let gitlink = format!("160000,{},vendor", head);
+    test_utils::run_git_command(&["update-index", "--add", "--cacheinfo", &gitlink], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add sources"], &test_dir);
+
+    // The working tree no longer matters
+    fs::remove_dir_all(test_dir.join("src")).expect("Failed to remove directory");
+
+    let items = tree::list_tree(repo_path.clone(), None, None).expect("Failed to list tree");
+    let listing: Vec<(&str, &str)> = items.iter().map(|i| (i.name.as_str(), i.type_.as_str())).collect();
+    assert_eq!(listing, vec![("src", "directory"), ("README.md", "file"), ("link", "symlink"), ("vendor", "submodule")]);
+    assert_eq!(items[1].size, Some("# Test Repository".len()));
+    assert_eq!(items[1].mode, 0o100644);
+    assert_eq!(items[3].oid, head);
+    assert_eq!(items[3].size, None);
+
+    let items = tree::list_tree(repo_path.clone(), None, Some("src".to_string())).expect("Failed to list tree");
+    let paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/nested", "src/main.rs"]);
+
+    // Older revisions have older trees
+    let items = tree::list_tree(repo_path.clone(), Some("HEAD~1".to_string()), None).expect("Failed to list tree");
+    assert_eq!(items.len(), 1);
+    assert!(tree::list_tree(repo_path.clone(), Some("HEAD~1".to_string()), Some("src".to_string())).is_err());
+    assert!(tree::list_tree(repo_path.clone(), None, Some("README.md".to_string())).is_err());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_read_blob_with_binary_and_size_cap() {
+    let test_dir = test_utils::create_test_repository("read_blob");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("image.bin"), [0x89, b'P', b'N', b'G', 0, 0, 1, 2]).expect("Failed to write file");
+    fs::write(test_dir.join("notes.txt"), "héllo wörld").expect("Failed to write file");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add files"], &test_dir);
+    fs::write(test_dir.join("README.md"), "# Changed").expect("Failed to write file");
+    test_utils::run_git_command(&["commit", "-am", "Change README"], &test_dir);
+
+    let blob = tree::read_blob(repo_path.clone(), Some("HEAD~1".to_string()), "README.md".to_string(), None)
+        .expect("Failed to read blob");
+    assert_eq!(blob.content.as_deref(), Some("# Test Repository"));
+    assert!(!blob.is_binary && !blob.truncated);
+
+    let blob = tree::read_blob(repo_path.clone(), None, "image.bin".to_string(), None).expect("Failed to read blob");
+    assert!(blob.is_binary);
+    assert_eq!(blob.size, 8);
+    assert_eq!(blob.content, None);
+
+    // The cap never splits a multi-byte character
+    let blob = tree::read_blob(repo_path.clone(), None, "notes.txt".to_string(), Some(2)).expect("Failed to read blob");
+    assert!(blob.truncated);
+    assert_eq!(blob.content.as_deref(), Some("h"));
+    assert_eq!(blob.size, "héllo wörld".len());
+
+    assert!(tree::read_blob(repo_path.clone(), None, "missing.txt".to_string(), None).is_err());
+
+    // Large blobs are only read up to the cap, whether loose or packed
+    let mut large = "line of text\n".repeat(2000).into_bytes();
+    fs::write(test_dir.join("large.txt"), &large).expect("Failed to write file");
+    large[9000] = 0;
+    fs::write(test_dir.join("large.bin"), &large).expect("Failed to write file");
+    large[100] = 0;
+    fs::write(test_dir.join("early.bin"), &large).expect("Failed to write file");
+    test_utils::run_git_command(&["add", "."], &test_dir);
+    test_utils::run_git_command(&["commit", "-m", "Add large files"], &test_dir);
+    for packed in [false, true] {
+        if packed {
+            test_utils::run_git_command(&["gc", "--quiet"], &test_dir);
+        }
+        let blob = tree::read_blob(repo_path.clone(), None, "large.txt".to_string(), Some(12)).expect("Failed to read blob");
+        assert!(blob.truncated && !blob.is_binary);
+        assert_eq!(blob.content.as_deref(), Some("line of text"));
+        assert_eq!(blob.size, large.len());
+        // Only the start decides whether a file is binary
+        let blob = tree::read_blob(repo_path.clone(), None, "large.bin".to_string(), Some(12)).expect("Failed to read blob");
+        assert!(!blob.is_binary);
+        let blob = tree::read_blob(repo_path.clone(), None, "early.bin".to_string(), Some(12)).expect("Failed to read blob");
+        assert!(blob.is_binary && !blob.truncated);
+        assert_eq!(blob.size, large.len());
+    }
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
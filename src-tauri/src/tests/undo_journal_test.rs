@@ -0,0 +1,118 @@
+use crate::git::{self, ResetMode};
+use crate::journal;
+use crate::test_utils;
+use git2::Repository;
+use std::fs;
+
+fn head_id(repo_path: &str) -> String {
+    git::get_commits(repo_path.to_string(), None, Some(1)).expect("Failed to get commits")[0].id.clone()
+}
+
+fn head_name(repo_path: &str) -> String {
+    let repo = Repository::open(repo_path).expect("Failed to open repository");
+    let head = repo.head().expect("Failed to read HEAD");
+    head.shorthand().unwrap_or("").to_string()
+}
+
+#[test]
+fn test_undo_commit_checkout_and_delete() {
+    let test_dir = test_utils::create_multi_branch_repository("undo_basic");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let main = head_name(&repo_path);
+    let base = head_id(&repo_path);
+
+    // Undoing a commit keeps its changes staged
+    fs::write(test_dir.join("notes.txt"), "Notes").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "notes.txt".to_string()).expect("Failed to stage file");
+    git::create_commit(repo_path.clone(), "Add notes".to_string(), None).expect("Failed to create commit");
+    let entry = journal::undo_last_operation(repo_path.clone()).expect("Failed to undo commit");
+    assert_eq!(entry.operation, "create_commit");
+    assert_eq!(head_id(&repo_path), base);
+    let status = git::get_status(repo_path.clone()).expect("Failed to get status");
+    assert_eq!(status.staged.len(), 1, "Undone commit should stay staged");
+    git::unstage_file(repo_path.clone(), "notes.txt".to_string()).expect("Failed to unstage file");
+    fs::remove_file(test_dir.join("notes.txt")).expect("Failed to remove file");
+
+    // Undoing a checkout returns to the previous branch and its files
+    git::checkout_branch(repo_path.clone(), "feature".to_string()).expect("Failed to check out");
+    assert!(test_dir.join("feature.txt").exists());
+    journal::undo_last_operation(repo_path.clone()).expect("Failed to undo checkout");
+    assert_eq!(head_name(&repo_path), main);
+    assert!(!test_dir.join("feature.txt").exists());
+
+    // Undoing a delete brings the branch back
+    let feature = Repository::open(&test_dir).unwrap().revparse_single("feature").unwrap().id().to_string();
+    git::delete_branch(repo_path.clone(), "feature".to_string()).expect("Failed to delete branch");
+    let entries = journal::get_undo_journal(repo_path.clone()).expect("Failed to read journal");
+    assert_eq!(entries[0].operation, "delete_branch");
+    journal::undo_last_operation(repo_path.clone()).expect("Failed to undo delete");
+    let branches = git::get_branches(repo_path.clone()).expect("Failed to get branches");
+    let restored = branches.iter().find(|b| b.name == "feature").expect("Branch should be restored");
+    assert_eq!(restored.commit_id, feature);
+
+    // Journals are written under the test configuration and removed with the repository
+    assert!(git::get_config_dir().unwrap().starts_with(std::env::temp_dir()));
+    test_utils::cleanup_test_repository(&test_dir);
+    let test_dir = test_utils::create_test_repository("undo_basic");
+    assert!(journal::get_undo_journal(test_dir.to_string_lossy().to_string()).unwrap().is_empty());
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_undo_hard_reset_restores_uncommitted_work() {
+    let test_dir = test_utils::create_test_repository("undo_hard_reset");
+    let repo_path = test_dir.to_string_lossy().to_string();
+
+    fs::write(test_dir.join("feature.txt"), "Feature").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "feature.txt".to_string()).expect("Failed to stage file");
+    let feature = git::create_commit(repo_path.clone(), "Add feature".to_string(), None).expect("Failed to create commit");
+    fs::write(test_dir.join("README.md"), "# Uncommitted edit").expect("Failed to write file");
+
+    let result = git::reset_to(repo_path.clone(), "HEAD~1".to_string(), ResetMode::Hard).expect("Failed to reset");
+    assert!(result.snapshot.is_some());
+    assert!(!test_dir.join("feature.txt").exists());
+
+    let entry = journal::undo_last_operation(repo_path.clone()).expect("Failed to undo reset");
+    assert_eq!(entry.snapshot, result.snapshot);
+    assert_eq!(head_id(&repo_path), feature.id);
+    assert!(test_dir.join("feature.txt").exists());
+    let readme = fs::read_to_string(test_dir.join("README.md")).expect("Failed to read file");
+    assert_eq!(readme, "# Uncommitted edit");
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_undo_merge_after_conflicts_and_refuse_moved_refs() {
+    let test_dir = test_utils::create_multi_branch_repository("undo_merge");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let base = head_id(&repo_path);
+
+    // A merge that stops on conflicts is recorded once it is concluded
+    let result = git::merge_branch(repo_path.clone(), "conflict-branch".to_string(), None).expect("Failed to merge");
+    assert!(result.has_conflicts);
+    fs::write(test_dir.join("README.md"), "# Resolved").expect("Failed to write file");
+    git::mark_resolved(repo_path.clone(), "README.md".to_string()).expect("Failed to mark resolved");
+    git::merge_continue(repo_path.clone(), None).expect("Failed to conclude merge");
+
+    let entries = journal::get_undo_journal(repo_path.clone()).expect("Failed to read journal");
+    assert_eq!(entries[0].operation, "merge_branch");
+    assert_eq!(entries[0].head_before.as_deref(), Some(base.as_str()));
+
+    journal::undo_last_operation(repo_path.clone()).expect("Failed to undo merge");
+    assert_eq!(head_id(&repo_path), base);
+    let readme = fs::read_to_string(test_dir.join("README.md")).expect("Failed to read file");
+    assert_eq!(readme, "# Modified in main branch");
+
+    // Refs moved outside the app make the last entry unsafe to undo
+    fs::write(test_dir.join("notes.txt"), "Notes").expect("Failed to write file");
+    git::stage_file(repo_path.clone(), "notes.txt".to_string()).expect("Failed to stage file");
+    git::create_commit(repo_path.clone(), "Add notes".to_string(), None).expect("Failed to create commit");
+    test_utils::run_git_command(&["commit", "--allow-empty", "-m", "Outside the app"], &test_dir);
+    let moved = head_id(&repo_path);
+    assert!(journal::undo_last_operation(repo_path.clone()).is_err());
+    assert_eq!(head_id(&repo_path), moved);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
@@ -0,0 +1,137 @@
+use crate::error::JanusError;
+use crate::git::{self, RepoInfo};
+use crate::test_utils;
+use crate::worktree::{self, AddWorktreeOptions};
+use std::fs;
+
+#[test]
+fn test_add_list_and_open_worktree() {
+    let test_dir = test_utils::create_multi_branch_repository("worktree_add");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let linked_dir = std::env::temp_dir().join("januslens_test_worktree_add_linked");
+    let _ = fs::remove_dir_all(&linked_dir);
+    let linked_path = linked_dir.to_string_lossy().to_string();
+
+    let info = worktree::add_worktree(repo_path.clone(), linked_path.clone(), "feature".to_string(), None)
+        .expect("Failed to add worktree");
+    assert_eq!(info.name.as_deref(), Some("januslens_test_worktree_add_linked"));
+    assert_eq!(info.branch.as_deref(), Some("feature"));
+    assert!(linked_dir.join("feature.txt").exists());
+
+    // A branch can only be checked out in one worktree
+    let other = std::env::temp_dir().join("januslens_test_worktree_add_other");
+    let _ = fs::remove_dir_all(&other);
+    assert!(worktree::add_worktree(repo_path.clone(), other.to_string_lossy().to_string(), "feature".to_string(), None).is_err());
+    assert!(worktree::add_worktree(repo_path.clone(), "relative/path".to_string(), "feature".to_string(), None).is_err());
+
+    let worktrees = worktree::list_worktrees(linked_path.clone()).expect("Failed to list worktrees");
+    assert_eq!(worktrees.len(), 2);
+    assert!(worktrees[0].is_main && !worktrees[0].is_current);
+    assert!(worktrees[1].is_current);
+    assert!(!worktrees[1].locked && !worktrees[1].prunable);
+
+    let repo_info = RepoInfo::from_path(linked_dir.clone()).expect("Failed to read repository info");
+    assert!(repo_info.is_worktree);
+    let main = repo_info.main_worktree.expect("Main worktree should be known");
+    assert_eq!(fs::canonicalize(main).unwrap(), fs::canonicalize(&test_dir).unwrap());
+    assert!(!RepoInfo::from_path(test_dir.clone()).unwrap().is_worktree);
+
+    // Removing refuses uncommitted changes unless forced
+    let name = info.name.unwrap();
+    fs::write(linked_dir.join("feature.txt"), "Changed").expect("Failed to write file");
+    assert!(worktree::remove_worktree(repo_path.clone(), name.clone(), None).is_err());
+    worktree::remove_worktree(repo_path.clone(), name, Some(true)).expect("Failed to remove worktree");
+    assert!(!linked_dir.exists());
+    assert_eq!(worktree::list_worktrees(repo_path.clone()).unwrap().len(), 1);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_lock_and_prune_worktrees() {
+    let test_dir = test_utils::create_test_repository("worktree_prune");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let linked_dir = std::env::temp_dir().join("januslens_test_worktree_prune_linked");
+    let _ = fs::remove_dir_all(&linked_dir);
+
+    let options = AddWorktreeOptions { create_branch: true, ..Default::default() };
+    let info = worktree::add_worktree(repo_path.clone(), linked_dir.to_string_lossy().to_string(), "topic".to_string(), Some(options))
+        .expect("Failed to add worktree");
+    let name = info.name.unwrap();
+    assert_eq!(info.branch.as_deref(), Some("topic"));
+
+    worktree::lock_worktree(repo_path.clone(), name.clone(), Some("On a USB drive".to_string())).expect("Failed to lock");
+    assert!(worktree::lock_worktree(repo_path.clone(), name.clone(), None).is_err());
+    let listed = &worktree::list_worktrees(repo_path.clone()).unwrap()[1];
+    assert!(listed.locked);
+    assert_eq!(listed.lock_reason.as_deref(), Some("On a USB drive"));
+    assert!(worktree::remove_worktree(repo_path.clone(), name.clone(), None).is_err());
+
+    // Locked worktrees survive pruning even when their directory is gone
+    fs::remove_dir_all(&linked_dir).expect("Failed to remove worktree directory");
+    assert!(worktree::prune_worktrees(repo_path.clone()).unwrap().is_empty());
+
+    worktree::unlock_worktree(repo_path.clone(), name.clone()).expect("Failed to unlock");
+    assert!(worktree::list_worktrees(repo_path.clone()).unwrap()[1].prunable);
+    assert_eq!(worktree::prune_worktrees(repo_path.clone()).unwrap(), vec![name]);
+    assert_eq!(worktree::list_worktrees(repo_path.clone()).unwrap().len(), 1);
+
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_worktree_runs_shared_hooks() {
+    let test_dir = test_utils::create_test_repository("worktree_hooks");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let linked_dir = std::env::temp_dir().join("januslens_test_worktree_hooks_linked");
+    let _ = fs::remove_dir_all(&linked_dir);
+    let linked_path = linked_dir.to_string_lossy().to_string();
+
+    let options = AddWorktreeOptions { create_branch: true, ..Default::default() };
+    worktree::add_worktree(repo_path.clone(), linked_path.clone(), "hooked".to_string(), Some(options))
+        .expect("Failed to add worktree");
+
+    // Hooks live in the common git directory, not the worktree's own
+    test_utils::install_hook(&test_dir, "pre-commit", "#!/bin/sh\necho \"blocked\" >&2\nexit 1\n");
+    fs::write(linked_dir.join("new.txt"), "New").expect("Failed to write file");
+    git::stage_file(linked_path.clone(), "new.txt".to_string()).expect("Failed to stage file");
+    match git::create_commit(linked_path.clone(), "Blocked".to_string(), None) {
+        Err(JanusError::HookFailed { hook, stderr, .. }) => {
+            assert_eq!(hook, "pre-commit");
+            assert!(stderr.contains("blocked"), "{}", stderr);
+        }
+        other => panic!("Expected the pre-commit hook to fail, got {:?}", other),
+    }
+
+    let _ = fs::remove_dir_all(&linked_dir);
+    test_utils::cleanup_test_repository(&test_dir);
+}
+
+#[test]
+fn test_worktree_name_collisions_and_failed_add() {
+    let test_dir = test_utils::create_test_repository("worktree_names");
+    let repo_path = test_dir.to_string_lossy().to_string();
+    let parent = std::env::temp_dir().join("januslens_test_worktree_names_linked");
+    let _ = fs::remove_dir_all(&parent);
+    fs::create_dir_all(parent.join("a")).expect("Failed to create directory");
+    fs::create_dir_all(parent.join("b")).expect("Failed to create directory");
+    let options = AddWorktreeOptions { create_branch: true, ..Default::default() };
+
+    // Worktrees whose paths end the same get distinct names
+    let first = worktree::add_worktree(repo_path.clone(), parent.join("a/feat").to_string_lossy().to_string(), "feat-a".to_string(), Some(options.clone()))
+        .expect("Failed to add first worktree");
+    let second = worktree::add_worktree(repo_path.clone(), parent.join("b/feat").to_string_lossy().to_string(), "feat-b".to_string(), Some(options.clone()))
+        .expect("Failed to add second worktree");
+    assert_eq!(first.name.as_deref(), Some("feat"));
+    assert_eq!(second.name.as_deref(), Some("feat1"));
+    assert_eq!(worktree::list_worktrees(repo_path.clone()).unwrap().len(), 3);
+
+    // A failed add does not leave the branch it created behind
+    let blocked = parent.join("missing/feat").to_string_lossy().to_string();
+    assert!(worktree::add_worktree(repo_path.clone(), blocked, "orphan".to_string(), Some(options)).is_err());
+    let branches = git::get_branches(repo_path.clone()).expect("Failed to get branches");
+    assert!(branches.iter().all(|b| b.name != "orphan"));
+
+    let _ = fs::remove_dir_all(&parent);
+    test_utils::cleanup_test_repository(&test_dir);
+}
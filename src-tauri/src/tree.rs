@@ -0,0 +1,172 @@
+use crate::error::JanusError;
+use crate::git::open_repo;
+use crate::paths;
+use git2::{Commit, ObjectType, Odb, Oid, Repository, Tree};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// Largest blob returned in full unless the caller asks for more
+pub const DEFAULT_MAX_BLOB_SIZE: usize = 1024 * 1024;
+
+/// How much of a blob git looks at to decide whether it is binary
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// An entry of a tree object at some revision
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreeItem {
+    pub name: String,
+    pub path: String,
+    /// "directory", "file", "symlink" or "submodule"
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub mode: i32,
+    pub oid: String,
+    /// Blob size in bytes, None for directories and submodules
+    pub size: Option<usize>,
+}
+
+/// Contents of a file at some revision
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlobContent {
+    pub path: String,
+    pub oid: String,
+    pub size: usize,
+    pub is_binary: bool,
+    /// True when `content` stops at the size cap
+    pub truncated: bool,
+    /// Text content, or None for binary files
+    pub content: Option<String>,
+}
+
+fn resolve_commit<'r>(repo: &'r Repository, revspec: &Option<String>) -> Result<Commit<'r>, JanusError> {
+    let revspec = revspec.as_deref().unwrap_or("HEAD");
+    repo.revparse_single(revspec).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+        error!("Failed to find {}: {}", revspec, e);
+        JanusError::GitError(format!("Failed to find {}: {}", revspec, e))
+    })
+}
+
+fn entry_type(mode: i32, kind: Option<ObjectType>) -> &'static str {
+    match kind {
+        Some(ObjectType::Tree) => "directory",
+        Some(ObjectType::Commit) => "submodule",
+        _ if mode == i32::from(git2::FileMode::Link) => "symlink",
+        _ => "file",
+    }
+}
+
+/// Treats content as binary when it has a NUL byte near the start, as git does
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_CHECK_SIZE)].contains(&0)
+}
+
+/// Reads the first `len` bytes of an object. Packed objects cannot be streamed, so those
+/// are read whole and cut.
+fn read_prefix(odb: &Odb, oid: Oid, len: usize) -> Result<Vec<u8>, JanusError> {
+    let mut bytes = Vec::with_capacity(len);
+    match odb.reader(oid) {
+        Ok((reader, _, _)) => {
+            reader.take(len as u64).read_to_end(&mut bytes)?;
+        }
+        Err(_) => bytes.extend_from_slice(&odb.read(oid)?.data()[..len]),
+    }
+    Ok(bytes)
+}
+
+/// Lists a directory of the tree at a revision, or the root when no directory is given
+#[tauri::command]
+pub fn list_tree(repo_path: String, revspec: Option<String>, directory: Option<String>) -> Result<Vec<TreeItem>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let commit = resolve_commit(&repo, &revspec)?;
+    let root = commit.tree()?;
+
+    let directory = match directory {
+        Some(directory) => paths::validate_dir_path(&repo, &directory)?,
+        None => String::new(),
+    };
+    let directory = directory.as_str();
+    let tree: Tree = if directory.is_empty() {
+        root
+    } else {
+        root.get_path(Path::new(directory))
+            .and_then(|entry| entry.to_object(&repo))
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(|_| JanusError::ValidationError(format!("{} is not a directory at {}", directory, commit.id())))?
+    };
+
+    let odb = repo.odb()?;
+    let mut items = Vec::new();
+    for entry in tree.iter() {
+        let name = entry.name().unwrap_or("").to_string();
+        let mode = entry.filemode();
+        let type_ = entry_type(mode, entry.kind());
+        let size = match type_ {
+            "file" | "symlink" => Some(odb.read_header(entry.id())?.0),
+            _ => None,
+        };
+
+        items.push(TreeItem {
+            path: if directory.is_empty() { name.clone() } else { format!("{}/{}", directory, name) },
+            name,
+            type_: type_.to_string(),
+            mode,
+            oid: entry.id().to_string(),
+            size,
+        });
+    }
+
+    // Sort: directories first, then files, both alphabetically
+    items.sort_by(|a, b| (a.type_ != "directory", &a.name).cmp(&(b.type_ != "directory", &b.name)));
+
+    Ok(items)
+}
+
+/// Reads a file at a revision without checking it out, up to `max_size` bytes
+#[tauri::command]
+pub fn read_blob(repo_path: String, revspec: Option<String>, file_path: String, max_size: Option<usize>) -> Result<BlobContent, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let file_path = paths::validate_file_path(&repo, &file_path)?;
+    let commit = resolve_commit(&repo, &revspec)?;
+    let max_size = max_size.unwrap_or(DEFAULT_MAX_BLOB_SIZE);
+
+    let entry = commit
+        .tree()?
+        .get_path(Path::new(&file_path))
+        .map_err(|_| JanusError::ValidationError(format!("{} does not exist at {}", file_path, commit.id())))?;
+    let odb = repo.odb()?;
+    let (size, kind) = odb.read_header(entry.id())?;
+    if kind != ObjectType::Blob {
+        return Err(JanusError::ValidationError(format!("{} is not a file at {}", file_path, commit.id())));
+    }
+
+    // Only read as much of a large blob as the cap and the binary check need
+    let wanted = max_size.saturating_add(1).max(BINARY_CHECK_SIZE);
+    let bytes = if size <= wanted {
+        odb.read(entry.id())?.data().to_vec()
+    } else {
+        read_prefix(&odb, entry.id(), wanted)?
+    };
+    let is_binary = looks_binary(&bytes);
+    let truncated = !is_binary && size > max_size;
+    let content = if is_binary {
+        None
+    } else {
+        // Cut before a UTF-8 continuation byte so the last character stays whole
+        let mut end = bytes.len().min(max_size);
+        while truncated && end > 0 && bytes[end] & 0b1100_0000 == 0b1000_0000 {
+            end -= 1;
+        }
+        Some(String::from_utf8_lossy(&bytes[..end]).to_string())
+    };
+
+    Ok(BlobContent {
+        path: file_path,
+        oid: entry.id().to_string(),
+        size,
+        is_binary,
+        truncated,
+        content,
+    })
+}
@@ -0,0 +1,281 @@
+use crate::error::JanusError;
+use crate::git::open_repo;
+use crate::paths;
+use git2::{BranchType, Repository, StatusOptions, Worktree, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A working tree of the repository, either the main one or a linked one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorktreeInfo {
+    /// Name under `.git/worktrees`, or None for the main worktree
+    pub name: Option<String>,
+    pub path: String,
+    pub is_main: bool,
+    /// True for the worktree the command was run from
+    pub is_current: bool,
+    /// Checked out branch, or None when HEAD is detached
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    /// True when the worktree's directory is gone and `prune_worktrees` would remove it
+    pub prunable: bool,
+}
+
+/// Options for `add_worktree`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AddWorktreeOptions {
+    /// Create the branch instead of checking out an existing one
+    pub create_branch: bool,
+    /// Where a new branch starts. Defaults to HEAD.
+    pub start_point: Option<String>,
+    /// Lock the new worktree straight away
+    pub lock: bool,
+}
+
+/// The git directory shared by all worktrees, read from the `commondir` file of a linked worktree
+pub(crate) fn common_dir(repo: &Repository) -> PathBuf {
+    if !repo.is_worktree() {
+        return repo.path().to_path_buf();
+    }
+    fs::read_to_string(repo.path().join("commondir"))
+        .ok()
+        .and_then(|dir| repo.path().join(dir.trim()).canonicalize().ok())
+        .unwrap_or_else(|| repo.path().to_path_buf())
+}
+
+/// Working directory of the main worktree, or the git directory of a bare repository
+pub(crate) fn main_worktree_path(repo: &Repository) -> PathBuf {
+    let common = common_dir(repo);
+    match Repository::open(&common).ok().and_then(|main| main.workdir().map(Path::to_path_buf)) {
+        Some(workdir) => workdir,
+        None => common,
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn head_state(repo: &Repository) -> (Option<String>, Option<String>) {
+    let Ok(head) = repo.head() else { return (None, None) };
+    let branch = if head.is_branch() { head.shorthand().map(|s| s.to_string()) } else { None };
+    (branch, head.target().map(|oid| oid.to_string()))
+}
+
+fn find_worktree(repo: &Repository, name: &str) -> Result<Worktree, JanusError> {
+    repo.find_worktree(name).map_err(|e| {
+        error!("Failed to find worktree {}: {}", name, e);
+        JanusError::GitError(format!("Failed to find worktree {}: {}", name, e))
+    })
+}
+
+fn linked_worktree_info(repo: &Repository, worktree: &Worktree) -> Result<WorktreeInfo, JanusError> {
+    let (locked, lock_reason) = match worktree.is_locked()? {
+        WorktreeLockStatus::Locked(reason) => (true, reason.filter(|r| !r.is_empty())),
+        WorktreeLockStatus::Unlocked => (false, None),
+    };
+    let (branch, head) = match Repository::open_from_worktree(worktree) {
+        Ok(linked) => head_state(&linked),
+        Err(_) => (None, None),
+    };
+    let current = repo.workdir().map(|dir| same_path(dir, worktree.path())).unwrap_or(false);
+
+    Ok(WorktreeInfo {
+        name: worktree.name().map(|s| s.to_string()),
+        path: worktree.path().to_string_lossy().to_string(),
+        is_main: false,
+        is_current: current,
+        branch,
+        head,
+        locked,
+        lock_reason,
+        prunable: worktree.is_prunable(None)?,
+    })
+}
+
+/// Lists the main worktree followed by the linked ones
+#[tauri::command]
+pub fn list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+
+    let main_path = main_worktree_path(&repo);
+    let (branch, head) = match Repository::open(common_dir(&repo)) {
+        Ok(main) => head_state(&main),
+        Err(_) => (None, None),
+    };
+    let mut worktrees = vec![WorktreeInfo {
+        name: None,
+        path: main_path.to_string_lossy().to_string(),
+        is_main: true,
+        is_current: !repo.is_worktree(),
+        branch,
+        head,
+        locked: false,
+        lock_reason: None,
+        prunable: false,
+    }];
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = find_worktree(&repo, name)?;
+        worktrees.push(linked_worktree_info(&repo, &worktree)?);
+    }
+    Ok(worktrees)
+}
+
+/// Adds a linked worktree at `path` with `branch` checked out, creating the branch if asked.
+/// The worktree is named after the last component of its path, with a number appended when
+/// that name is taken, like git does.
+#[tauri::command]
+pub fn add_worktree(
+    repo_path: String,
+    path: String,
+    branch: String,
+    options: Option<AddWorktreeOptions>,
+) -> Result<WorktreeInfo, JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let options = options.unwrap_or_default();
+    paths::validate_cli_arg(&branch)?;
+
+    let target = PathBuf::from(&path);
+    if !target.is_absolute() {
+        return Err(JanusError::ValidationError(format!("Worktree path {} must be absolute", path)));
+    }
+    if target.exists() && fs::read_dir(&target)?.next().is_some() {
+        return Err(JanusError::ValidationError(format!("{} already exists and is not empty", path)));
+    }
+    let base_name = target
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| JanusError::ValidationError(format!("{} does not end in a directory name", path)))?;
+    let worktrees_dir = common_dir(&repo).join("worktrees");
+    let name = (0..)
+        .map(|n| if n == 0 { base_name.to_string() } else { format!("{}{}", base_name, n) })
+        .find(|name| !worktrees_dir.join(name).exists())
+        .unwrap_or_default();
+
+    let mut branch_ref = if options.create_branch {
+        let start_point = options.start_point.as_deref().unwrap_or("HEAD");
+        let start = repo.revparse_single(start_point).and_then(|obj| obj.peel_to_commit()).map_err(|e| {
+            error!("Failed to find {}: {}", start_point, e);
+            JanusError::GitError(format!("Failed to find {}: {}", start_point, e))
+        })?;
+        repo.branch(&branch, &start, false).map_err(|e| {
+            error!("Failed to create branch {}: {}", branch, e);
+            JanusError::GitError(format!("Failed to create branch {}: {}", branch, e))
+        })?
+    } else {
+        repo.find_branch(&branch, BranchType::Local).map_err(|e| {
+            error!("Failed to find branch {}: {}", branch, e);
+            JanusError::GitError(format!("Failed to find branch {}: {}", branch, e))
+        })?
+    };
+
+    let mut add_options = WorktreeAddOptions::new();
+    add_options.lock(options.lock).reference(Some(branch_ref.get()));
+    let worktree = match repo.worktree(&name, &target, Some(&add_options)) {
+        Ok(worktree) => worktree,
+        Err(e) => {
+            error!("Failed to add worktree {} at {}: {}", name, path, e);
+            // Don't leave behind a branch that only existed for this worktree
+            if options.create_branch {
+                if let Err(e) = branch_ref.delete() {
+                    warn!("Failed to delete branch {} after a failed worktree add: {}", branch, e);
+                }
+            }
+            return Err(JanusError::GitError(format!("Failed to add worktree at {}: {}", path, e)));
+        }
+    };
+
+    info!("Added worktree {} at {} for {}", name, path, branch);
+    linked_worktree_info(&repo, &worktree)
+}
+
+/// Locks a linked worktree so it is not pruned or removed, e.g. while it lives on removable media
+#[tauri::command]
+pub fn lock_worktree(repo_path: String, name: String, reason: Option<String>) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let worktree = find_worktree(&repo, &name)?;
+    if let WorktreeLockStatus::Locked(_) = worktree.is_locked()? {
+        return Err(JanusError::ValidationError(format!("Worktree {} is already locked", name)));
+    }
+
+    worktree.lock(reason.as_deref())?;
+    info!("Locked worktree {}", name);
+    Ok(())
+}
+
+/// Unlocks a linked worktree so it can be pruned or removed again
+#[tauri::command]
+pub fn unlock_worktree(repo_path: String, name: String) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let worktree = find_worktree(&repo, &name)?;
+    if let WorktreeLockStatus::Unlocked = worktree.is_locked()? {
+        return Err(JanusError::ValidationError(format!("Worktree {} is not locked", name)));
+    }
+
+    worktree.unlock()?;
+    info!("Unlocked worktree {}", name);
+    Ok(())
+}
+
+/// Removes the records of worktrees whose directories are gone, skipping locked ones.
+/// Returns the names that were pruned.
+#[tauri::command]
+pub fn prune_worktrees(repo_path: String) -> Result<Vec<String>, JanusError> {
+    let repo = open_repo(&repo_path)?;
+
+    let mut pruned = Vec::new();
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = find_worktree(&repo, name)?;
+        if worktree.is_prunable(None)? {
+            worktree.prune(None)?;
+            pruned.push(name.to_string());
+        }
+    }
+
+    info!("Pruned {} worktree(s)", pruned.len());
+    Ok(pruned)
+}
+
+/// Deletes a linked worktree's directory and its records. Without `force`, worktrees that are
+/// locked or have uncommitted changes are left alone.
+#[tauri::command]
+pub fn remove_worktree(repo_path: String, name: String, force: Option<bool>) -> Result<(), JanusError> {
+    let repo = open_repo(&repo_path)?;
+    let force = force.unwrap_or(false);
+    let worktree = find_worktree(&repo, &name)?;
+
+    if repo.workdir().map(|dir| same_path(dir, worktree.path())).unwrap_or(false) {
+        return Err(JanusError::ValidationError(format!("Cannot remove worktree {} while it is open", name)));
+    }
+    if !force {
+        if let WorktreeLockStatus::Locked(_) = worktree.is_locked()? {
+            return Err(JanusError::ValidationError(format!("Worktree {} is locked", name)));
+        }
+        if let Ok(linked) = Repository::open_from_worktree(&worktree) {
+            let mut status_options = StatusOptions::new();
+            status_options.include_untracked(true).include_ignored(false);
+            if !linked.statuses(Some(&mut status_options))?.is_empty() {
+                return Err(JanusError::ValidationError(format!("Worktree {} has uncommitted changes", name)));
+            }
+        }
+    }
+
+    let mut prune_options = WorktreePruneOptions::new();
+    prune_options.valid(true).locked(force).working_tree(true);
+    worktree.prune(Some(&mut prune_options)).map_err(|e| {
+        error!("Failed to remove worktree {}: {}", name, e);
+        JanusError::GitError(format!("Failed to remove worktree {}: {}", name, e))
+    })?;
+
+    info!("Removed worktree {}", name);
+    Ok(())
+}